@@ -2,7 +2,7 @@ use super::{
     bomb::{Bomb, DragState},
     player::{self, Player},
 };
-use twors::{dimensions::Dimensions, prelude::*, shape_factory};
+use twors::{Font, TextBaseline, dimensions::Dimensions, prelude::*, shape_factory};
 
 #[derive(Component)]
 pub struct Battlefield {
@@ -18,6 +18,10 @@ pub struct Battlefield {
 
 const SIZE: f32 = 800.0;
 const OFFSET: f32 = 50.0;
+const COUNTER_PADDING: f32 = 10.0;
+
+/// The battlefield with a margin around it - in logical pixels, scaled to fit the window
+pub const SCREEN_SIZE: f32 = SIZE + 2.0 * OFFSET;
 
 impl Battlefield {
     pub fn new() -> Self {
@@ -29,21 +33,25 @@ impl Battlefield {
                 SIZE / 2.0 + OFFSET,
                 SIZE / 2.0 + OFFSET,
             )),
-            renderables: vec![Renderable {
-                transform: Transform::default(),
-                vertices: shape_factory::square(SIZE),
-                style: |ctx: &CanvasRenderingContext2d| {
-                    ctx.set_fill_style_str("green");
-                    ctx.set_line_width(1.0);
-                    ctx.set_stroke_style_str("black");
-                    ctx.stroke();
-                    ctx.fill();
-                },
-                layer: twors::Layer::Five,
-            }],
+            renderables: vec![
+                Renderable::new(shape_factory::square(SIZE), twors::Layer::Five)
+                    .with_style(Style::filled("green").with_stroke("black")),
+                Renderable::new(Battlefield::bomb_counter(0), twors::Layer::One)
+                    .with_transform(Transform::from_position(Vertex2::new(
+                        -SIZE / 2.0 + COUNTER_PADDING,
+                        -SIZE / 2.0 + COUNTER_PADDING,
+                    )))
+                    .with_style(Style::filled("white").with_stroke("black")),
+            ],
         }
     }
 
+    fn bomb_counter(bombs: usize) -> Text {
+        Text::new(&format!("Bombs: {bombs}"))
+            .with_font(Font::new("monospace", 24.0))
+            .with_baseline(TextBaseline::Top)
+    }
+
     fn drag_bombs(&mut self, mouse_position: Vertex2<f32>) {
         for bomb in &mut self.bombs {
             if let DragState::Dragged(relative_mouse_position) = bomb.drag_state() {
@@ -108,6 +116,46 @@ impl ComponentLifecycle for Battlefield {
 
         if ctx.input.mouse.is_pressed(Mouse::LMB) {
             self.bombs.push(Bomb::new(self.player.transform.position));
+            self.renderables[1].shape = Battlefield::bomb_counter(self.bombs.len()).into();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twors::{Shape, TestEngine};
+
+    const DELTA_TIME: f32 = 1.0 / 60.0;
+
+    fn player_position(engine: &TestEngine) -> Vertex2<f32> {
+        let battlefield = &engine.components()[0];
+        let player = battlefield.children()[0];
+        player.transform().absolute().position
+    }
+
+    #[test]
+    fn player_is_restricted_within_field() {
+        let mut engine = TestEngine::new(vec![Box::new(Battlefield::new())]);
+
+        engine.press_key(Key::D);
+        engine.press_key(Key::S);
+        engine.step_many(600, DELTA_TIME).unwrap();
+
+        let max = OFFSET + SIZE - player::SIZE / 2.0;
+        assert_eq!(player_position(&engine), Vertex2::new(max, max));
+    }
+
+    #[test]
+    fn bomb_is_spawned_on_click() {
+        let mut engine = TestEngine::new(vec![Box::new(Battlefield::new())]);
+
+        engine.press_mouse(Mouse::LMB);
+        engine.step(DELTA_TIME).unwrap();
+        engine.step(DELTA_TIME).unwrap();
+
+        assert_eq!(engine.components()[0].children().len(), 2);
+        let counter = &engine.components()[0].renderables()[1];
+        assert!(matches!(&counter.shape, Shape::Text(text) if text.content == "Bombs: 1"));
+    }
+}
@@ -22,18 +22,10 @@ impl Bomb {
             drag_state: DragState::Resting,
 
             transform: Transform::from_position(position),
-            renderables: vec![Renderable {
-                transform: Transform::default(),
-                vertices: shape_factory::square(SIZE),
-                style: |ctx: &CanvasRenderingContext2d| {
-                    ctx.set_fill_style_str("yellow");
-                    ctx.set_line_width(1.0);
-                    ctx.set_stroke_style_str("black");
-                    ctx.stroke();
-                    ctx.fill();
-                },
-                layer: twors::Layer::Two,
-            }],
+            renderables: vec![
+                Renderable::new(shape_factory::square(SIZE), twors::Layer::Two)
+                    .with_style(Style::filled("yellow").with_stroke("black")),
+            ],
         }
     }
 
@@ -2,7 +2,7 @@ mod battlefield;
 mod bomb;
 mod player;
 
-use battlefield::Battlefield;
+use battlefield::{Battlefield, SCREEN_SIZE};
 use twors::{Result, prelude::*};
 use wasm_bindgen::prelude::wasm_bindgen;
 
@@ -10,7 +10,8 @@ use wasm_bindgen::prelude::wasm_bindgen;
 pub fn entry(canvas_id: &str) -> Result<()> {
     console_log::init().unwrap();
 
-    let engine = Engine::new(canvas_id, vec![Box::new(Battlefield::new())])?;
+    let engine = Engine::new(canvas_id, vec![Box::new(Battlefield::new())])?
+        .with_scaling(Scaling::Fixed(Vertex2::new(SCREEN_SIZE, SCREEN_SIZE)));
     engine.run()?;
 
     Ok(())
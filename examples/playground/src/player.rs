@@ -8,6 +8,7 @@ use twors::prelude::*;
 pub const SIZE: f32 = 60.0;
 
 const SPEED: f32 = 200.0;
+const ROTATION_SPEED: f32 = std::f32::consts::PI;
 
 #[derive(Component)]
 pub struct Player {
@@ -61,5 +62,13 @@ impl ComponentLifecycle for Player {
                 y: SPEED * ctx.delta_time(),
             };
         }
+
+        if ctx.input.keyboard.is_down(Key::Q) {
+            self.transform.rotation -= ROTATION_SPEED * ctx.delta_time();
+        }
+
+        if ctx.input.keyboard.is_down(Key::E) {
+            self.transform.rotation += ROTATION_SPEED * ctx.delta_time();
+        }
     }
 }
@@ -10,18 +10,11 @@ impl Mouth {
     pub fn new(position: Vertex2<f32>) -> Self {
         Self {
             transform: Transform::from_position(position),
-            renderables: vec![Renderable {
-                transform: Transform::from_position(position),
-                vertices: shape_factory::rectangle(15.0, 2.0),
-                style: |ctx: &CanvasRenderingContext2d| {
-                    ctx.set_fill_style_str("black");
-                    ctx.set_line_width(1.0);
-                    ctx.set_stroke_style_str("black");
-                    ctx.stroke();
-                    ctx.fill();
-                },
-                layer: twors::Layer::One,
-            }],
+            renderables: vec![
+                Renderable::new(shape_factory::rectangle(15.0, 2.0), twors::Layer::One)
+                    .with_transform(Transform::from_position(position))
+                    .with_style(Style::filled("black").with_stroke("black")),
+            ],
         }
     }
 }
@@ -0,0 +1,243 @@
+//! Represents a 2D affine transformation (translation, rotation, scale and skew) as a 3x3 matrix.
+
+use crate::{Transform, Vertex2};
+use std::ops::{Mul, MulAssign};
+
+/// A 3x3 matrix with an implicit last row of `(0, 0, 1)` - the fields follow the naming of the
+/// canvas' `setTransform(a, b, c, d, e, f)`:
+///
+/// ```text
+/// | a c e |
+/// | b d f |
+/// | 0 0 1 |
+/// ```
+///
+/// Matrices are combined via multiplication - `parent * child` maps points from the space of the
+/// child to the space of the parent.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Affine2 {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Affine2 {
+    pub const IDENTITY: Affine2 = Affine2::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub const fn from_translation(translation: Vertex2<f32>) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, translation.x, translation.y)
+    }
+
+    pub const fn from_scale(scale: Vertex2<f32>) -> Self {
+        Self::new(scale.x, 0.0, 0.0, scale.y, 0.0, 0.0)
+    }
+
+    /// Positive values rotate clockwise since the canvas' `y` axis points down.
+    pub fn from_rotation(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Returns `None` if the matrix can't be inverted (e.g. it has a scale of `0`).
+    pub fn inverse(&self) -> Option<Affine2> {
+        let determinant = self.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        let a = self.d / determinant;
+        let b = -self.b / determinant;
+        let c = -self.c / determinant;
+        let d = self.a / determinant;
+        Some(Self::new(
+            a,
+            b,
+            c,
+            d,
+            -(a * self.e + c * self.f),
+            -(b * self.e + d * self.f),
+        ))
+    }
+
+    pub fn transform_point(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        self.transform_vector(point) + self.translation()
+    }
+
+    /// Like [transform_point](Self::transform_point), but ignores the translation - use it for
+    /// directions and offsets.
+    pub fn transform_vector(&self, vector: Vertex2<f32>) -> Vertex2<f32> {
+        Vertex2::new(
+            self.a * vector.x + self.c * vector.y,
+            self.b * vector.x + self.d * vector.y,
+        )
+    }
+
+    pub fn translation(&self) -> Vertex2<f32> {
+        Vertex2::new(self.e, self.f)
+    }
+}
+
+impl Default for Affine2 {
+    fn default() -> Self {
+        Affine2::IDENTITY
+    }
+}
+
+impl Mul for Affine2 {
+    type Output = Affine2;
+
+    fn mul(self, rhs: Affine2) -> Self::Output {
+        Self::new(
+            self.a * rhs.a + self.c * rhs.b,
+            self.b * rhs.a + self.d * rhs.b,
+            self.a * rhs.c + self.c * rhs.d,
+            self.b * rhs.c + self.d * rhs.d,
+            self.a * rhs.e + self.c * rhs.f + self.e,
+            self.b * rhs.e + self.d * rhs.f + self.f,
+        )
+    }
+}
+
+impl Mul<&Affine2> for &Affine2 {
+    type Output = Affine2;
+
+    fn mul(self, rhs: &Affine2) -> Self::Output {
+        *self * *rhs
+    }
+}
+
+impl MulAssign for Affine2 {
+    fn mul_assign(&mut self, rhs: Affine2) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<&Transform> for Affine2 {
+    fn from(transform: &Transform) -> Self {
+        Affine2::from_translation(transform.position + transform.pivot)
+            * Affine2::from_rotation(transform.rotation)
+            * Affine2::from_scale(transform.scale)
+            * Affine2::from_translation(Vertex2::default() - transform.pivot)
+    }
+}
+
+/// Decomposes the matrix into a position, rotation and scale (with a `(0, 0)` pivot and no
+/// parent).
+///
+/// > **Note:** skew can't be represented by a [Transform](Transform) and is lost.
+impl From<Affine2> for Transform {
+    fn from(matrix: Affine2) -> Self {
+        let scale_x = (matrix.a * matrix.a + matrix.b * matrix.b).sqrt();
+        let scale_y = if scale_x == 0.0 {
+            (matrix.c * matrix.c + matrix.d * matrix.d).sqrt()
+        } else {
+            matrix.determinant() / scale_x
+        };
+
+        Transform {
+            position: matrix.translation(),
+            scale: Vertex2::new(scale_x, scale_y),
+            rotation: matrix.b.atan2(matrix.a),
+            ..Transform::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{EPSILON, assert_near};
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn identity() {
+        let point = Vertex2::new(3.0, 4.0);
+        assert_eq!(Affine2::IDENTITY.transform_point(point), point);
+        assert_eq!(Affine2::default(), Affine2::IDENTITY);
+    }
+
+    #[test]
+    fn mul() {
+        let parent = Affine2::from_translation(Vertex2::new(10.0, 0.0))
+            * Affine2::from_scale(Vertex2::new(2.0, 2.0));
+        let child = Affine2::from_translation(Vertex2::new(5.0, 0.0));
+
+        // The offset of the child is scaled by the parent
+        let point = (parent * child).transform_point(Vertex2::new(1.0, 0.0));
+        assert_near(point, Vertex2::new(22.0, 0.0));
+    }
+
+    #[test]
+    fn transform_vector() {
+        let matrix =
+            Affine2::from_translation(Vertex2::new(10.0, 10.0)) * Affine2::from_rotation(FRAC_PI_2);
+        assert_near(
+            matrix.transform_vector(Vertex2::new(1.0, 0.0)),
+            Vertex2::new(0.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn inverse() {
+        let matrix = Affine2::from_translation(Vertex2::new(10.0, -5.0))
+            * Affine2::from_rotation(0.5)
+            * Affine2::from_scale(Vertex2::new(2.0, 3.0));
+        let inverse = matrix.inverse().unwrap();
+
+        let point = Vertex2::new(7.0, 8.0);
+        assert_near(
+            inverse.transform_point(matrix.transform_point(point)),
+            point,
+        );
+        assert_near((matrix * inverse).transform_point(point), point);
+    }
+
+    #[test]
+    fn inverse_singular() {
+        assert_eq!(Affine2::from_scale(Vertex2::new(0.0, 1.0)).inverse(), None);
+    }
+
+    #[test]
+    fn transform_round_trip() {
+        let transform = Transform {
+            scale: Vertex2::new(2.0, 3.0),
+            rotation: 0.5,
+            ..Transform::from_position(Vertex2::new(10.0, 20.0))
+        };
+
+        let actual = Transform::from(Affine2::from(&transform));
+        assert_near(actual.position, transform.position);
+        assert_near(actual.scale, transform.scale);
+        assert!((actual.rotation - transform.rotation).abs() < EPSILON);
+    }
+
+    #[test]
+    fn transform_pivot() {
+        let transform = Transform {
+            rotation: FRAC_PI_2,
+            pivot: Vertex2::new(-10.0, 0.0),
+            ..Transform::default()
+        };
+
+        let matrix = Affine2::from(&transform);
+        assert_near(
+            matrix.transform_point(Vertex2::new(-10.0, 0.0)),
+            Vertex2::new(-10.0, 0.0),
+        );
+        assert_near(
+            matrix.transform_point(Vertex2::default()),
+            Vertex2::new(-10.0, 10.0),
+        );
+    }
+}
@@ -0,0 +1,589 @@
+//! Sprite-sheet animation - a [SpriteSheet](SpriteSheet) describes the frames and the named
+//! [Clip](Clip)s of an image, while an [Animator](Animator) plays the clips and points a
+//! [Sprite](crate::Sprite) at the current frame. The [AnimatedSprite](AnimatedSprite) component
+//! does the latter on its own every frame.
+//!
+//! Sheets can be built manually (see [SpriteSheet::from_grid]) or imported from the JSON exported
+//! by Aseprite and TexturePacker (see [SpriteSheet::from_aseprite] and
+//! [SpriteSheet::from_texture_packer]) - they can also be loaded as assets, in which case the
+//! format is detected automatically.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use twors::{
+//!     Result,
+//!     animation::{AnimatedSprite, AnimationEvent, Animator, PlayMode, SpriteSheet},
+//!     prelude::*,
+//! };
+//!
+//! fn player(image: twors::ImageHandle) -> Result<AnimatedSprite> {
+//!     // 8 frames of 16x16 pixels in a single row - 0.1 seconds each
+//!     let sheet = SpriteSheet::from_grid(Vertex2::new(16.0, 16.0), 8, 8, 0.1)
+//!         .with_clip("idle", &[0, 1], PlayMode::Loop)?
+//!         .with_clip("attack", &[2, 3, 4, 5, 6, 7], PlayMode::Once)?;
+//!     let mut animator = Animator::new(sheet);
+//!     animator.play("idle")?;
+//!
+//!     Ok(AnimatedSprite::new(Sprite::new(image), animator, Layer::One))
+//! }
+//!
+//! // Call it in the `update` of the parent - the child has already advanced this frame
+//! fn animate(player: &mut AnimatedSprite) -> Result<()> {
+//!     let attack_finished = player
+//!         .events()
+//!         .contains(&AnimationEvent::Finished("attack".to_owned()));
+//!     if attack_finished {
+//!         player.animator.play("idle")?;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+mod import;
+
+use crate::{
+    Asset, ComponentLifecycle, Context, Layer, Renderable, Shape, Sprite, Transform, Vertex2,
+    dimensions::Dimensions, error::Result, wasm_assert,
+};
+use log::warn;
+use std::collections::HashMap;
+use twors_derive::Component;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// The part of the sheet image in image pixels
+    pub source: Dimensions,
+
+    /// In seconds
+    pub duration: f32,
+}
+
+impl Frame {
+    /// Frames without a positive duration are skipped by the [Animator](Animator).
+    fn has_duration(&self) -> bool {
+        self.duration > 0.0
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum PlayMode {
+    /// Starts over after the last frame
+    #[default]
+    Loop,
+    /// Plays forward, then backward, then forward again, ...
+    PingPong,
+    /// Stops at the last frame
+    Once,
+}
+
+/// A named sequence of frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip {
+    pub frames: Vec<Frame>,
+    pub mode: PlayMode,
+}
+
+impl Clip {
+    /// Fails if there are no frames or if any frame doesn't have a positive duration - the `name`
+    /// is only used for the error.
+    pub(crate) fn new(name: &str, frames: Vec<Frame>, mode: PlayMode) -> Result<Self> {
+        if frames.is_empty() {
+            return Err(format!("The clip {name} has no frames").into());
+        }
+        if frames.iter().any(|frame| !frame.has_duration()) {
+            return Err(format!("The clip {name} has a frame without a positive duration").into());
+        }
+        Ok(Self { frames, mode })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct SpriteSheet {
+    /// Every frame of the sheet in order
+    pub frames: Vec<Frame>,
+    pub clips: HashMap<String, Clip>,
+}
+
+impl SpriteSheet {
+    /// Frames of the same size laid out left to right, top to bottom - `count` frames in rows of
+    /// `columns` frames.
+    pub fn from_grid(
+        frame_size: Vertex2<f32>,
+        columns: usize,
+        count: usize,
+        duration: f32,
+    ) -> Self {
+        wasm_assert!(columns > 0);
+
+        let frames = (0..count)
+            .map(|index| {
+                let column = (index % columns) as f32;
+                let row = (index / columns) as f32;
+                Frame {
+                    source: Dimensions::from_top_left(
+                        Vertex2::new(column * frame_size.x, row * frame_size.y),
+                        frame_size.x,
+                        frame_size.y,
+                    ),
+                    duration,
+                }
+            })
+            .collect();
+
+        Self {
+            frames,
+            clips: HashMap::default(),
+        }
+    }
+
+    /// Adds a clip made of the frames at the given indices (in order) - fails if there are no
+    /// indices, if any of them is out of range or if any of the frames doesn't have a positive
+    /// duration.
+    pub fn with_clip(mut self, name: &str, frames: &[usize], mode: PlayMode) -> Result<Self> {
+        let frames = frames
+            .iter()
+            .map(|index| {
+                self.frames.get(*index).copied().ok_or_else(|| {
+                    format!("The clip {name} has an out of range frame {index}").into()
+                })
+            })
+            .collect::<Result<Vec<Frame>>>()?;
+        let clip = Clip::new(name, frames, mode)?;
+        self.clips.insert(name.to_owned(), clip);
+        Ok(self)
+    }
+}
+
+/// Detects whether the JSON was exported by Aseprite or TexturePacker - TexturePacker frames get
+/// a duration of `0.1` seconds (it doesn't export durations).
+impl Asset for SpriteSheet {
+    fn decode(bytes: Vec<u8>) -> Result<Self> {
+        const TEXTURE_PACKER_FRAME_DURATION: f32 = 0.1;
+
+        let json = serde_json::Value::decode(bytes)?;
+        if import::is_aseprite(&json) {
+            SpriteSheet::from_aseprite(&json)
+        } else {
+            SpriteSheet::from_texture_packer(&json, TEXTURE_PACKER_FRAME_DURATION)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationEvent {
+    /// A `Once` clip reached its last frame
+    Finished(String),
+    /// A `Loop` or `PingPong` clip started over
+    Looped(String),
+}
+
+/// Plays the clips of a [SpriteSheet](SpriteSheet) - store it in a component, advance it with the
+/// delta time in `update` and [apply](Self::apply) it to the sprite of a renderable.
+#[derive(Clone, Debug)]
+pub struct Animator {
+    sheet: SpriteSheet,
+    clip: Option<String>,
+    frame: usize,
+    /// `-1` while a `PingPong` clip plays backward
+    direction: isize,
+    elapsed: f32,
+    finished: bool,
+    speed: f32,
+}
+
+impl Animator {
+    pub fn new(sheet: SpriteSheet) -> Self {
+        Self {
+            sheet,
+            clip: None,
+            frame: 0,
+            direction: 1,
+            elapsed: 0.0,
+            finished: false,
+            speed: 1.0,
+        }
+    }
+
+    pub fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+
+    /// Starts the clip from its first frame - does nothing if the clip is already playing, so it's
+    /// fine to call it every frame.
+    ///
+    /// Fails if the sheet has no such clip, in which case the current clip keeps playing.
+    pub fn play(&mut self, clip: &str) -> Result<()> {
+        if self.clip.as_deref() != Some(clip) {
+            if !self.sheet.clips.contains_key(clip) {
+                return Err(format!("The sprite sheet has no clip {clip}").into());
+            }
+            self.clip = Some(clip.to_owned());
+            self.restart();
+        }
+        Ok(())
+    }
+
+    /// Starts the current clip over.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.direction = 1;
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+
+    /// The playback speed multiplier - `2.0` plays twice as fast, `0.0` pauses.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Negative (and NaN) speeds are replaced with `0.0` - clips can't be played backward.
+    pub fn set_speed(&mut self, speed: f32) {
+        if speed.is_sign_negative() || speed.is_nan() {
+            warn!("The animation speed {speed} isn't supported - the animation is paused");
+            self.speed = 0.0;
+        } else {
+            self.speed = speed;
+        }
+    }
+
+    /// The name of the clip that's playing.
+    pub fn clip(&self) -> Option<&str> {
+        self.clip.as_deref()
+    }
+
+    /// `true` once a `Once` clip has reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The index of the current frame within the clip.
+    pub fn frame_index(&self) -> usize {
+        self.frame
+    }
+
+    pub fn frame(&self) -> Option<&Frame> {
+        self.current_clip()?.frames.get(self.frame)
+    }
+
+    /// Advances the animation by the delta time (in seconds) - multiple frames may be skipped if
+    /// the delta time is long enough.
+    pub fn update(&mut self, delta_time: f32) -> Vec<AnimationEvent> {
+        let mut events = Vec::default();
+        let Some(name) = self.clip.clone() else {
+            return events;
+        };
+        if self.finished {
+            return events;
+        }
+
+        self.elapsed += delta_time * self.speed;
+        // The clips can be changed via the public fields of the sheet, so nothing is assumed
+        while let Some(clip) = self.sheet.clips.get(&name) {
+            let Some(frame) = clip.frames.get(self.frame) else {
+                break;
+            };
+            if !frame.has_duration() {
+                // Skipping every frame of the clip would never end
+                if !clip.frames.iter().any(Frame::has_duration) {
+                    break;
+                }
+            } else if self.elapsed < frame.duration {
+                break;
+            } else {
+                self.elapsed -= frame.duration;
+            }
+
+            let last = clip.frames.len() - 1;
+            match clip.mode {
+                PlayMode::Loop if self.frame == last => {
+                    self.frame = 0;
+                    events.push(AnimationEvent::Looped(name.clone()));
+                }
+                PlayMode::Loop => self.frame += 1,
+                PlayMode::Once if self.frame == last => {
+                    self.finished = true;
+                    self.elapsed = 0.0;
+                    events.push(AnimationEvent::Finished(name.clone()));
+                    break;
+                }
+                PlayMode::Once => self.frame += 1,
+                PlayMode::PingPong if last == 0 => {
+                    events.push(AnimationEvent::Looped(name.clone()));
+                }
+                PlayMode::PingPong => {
+                    if (self.frame == last && self.direction > 0)
+                        || (self.frame == 0 && self.direction < 0)
+                    {
+                        self.direction = -self.direction;
+                    }
+                    self.frame = self.frame.saturating_add_signed(self.direction);
+                    if self.frame == 0 {
+                        events.push(AnimationEvent::Looped(name.clone()));
+                    }
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Points the sprite at the current frame.
+    pub fn apply(&self, sprite: &mut Sprite) {
+        if let Some(frame) = self.frame() {
+            sprite.source = Some(frame.source);
+        }
+    }
+
+    fn current_clip(&self) -> Option<&Clip> {
+        self.sheet.clips.get(self.clip.as_ref()?)
+    }
+}
+
+/// A sprite that's advanced by its [Animator](Animator) every frame - add it as a child of the
+/// component that decides which clip plays (children are updated before their parents, so the
+/// parent sees the [events](Self::events) of the current frame).
+#[derive(Component)]
+pub struct AnimatedSprite {
+    pub animator: Animator,
+    events: Vec<AnimationEvent>,
+
+    pub transform: Transform,
+    renderables: Vec<Renderable>,
+}
+
+impl AnimatedSprite {
+    pub fn new(mut sprite: Sprite, animator: Animator, layer: Layer) -> Self {
+        animator.apply(&mut sprite);
+        Self {
+            animator,
+            events: Vec::default(),
+            transform: Transform::default(),
+            renderables: vec![Renderable::new(sprite, layer)],
+        }
+    }
+
+    pub fn sprite(&self) -> &Sprite {
+        match &self.renderables[0].shape {
+            Shape::Sprite(sprite) => sprite,
+            _ => unreachable!("The renderable of an animated sprite is always a sprite"),
+        }
+    }
+
+    /// The sprite's `source` is overwritten by the animator every frame.
+    pub fn sprite_mut(&mut self) -> &mut Sprite {
+        match &mut self.renderables[0].shape {
+            Shape::Sprite(sprite) => sprite,
+            _ => unreachable!("The renderable of an animated sprite is always a sprite"),
+        }
+    }
+
+    /// The events of the last update.
+    pub fn events(&self) -> &[AnimationEvent] {
+        &self.events
+    }
+}
+
+impl ComponentLifecycle for AnimatedSprite {
+    fn update(&mut self, ctx: &mut Context) {
+        self.events = self.animator.update(ctx.delta_time());
+        let Shape::Sprite(sprite) = &mut self.renderables[0].shape else {
+            unreachable!("The renderable of an animated sprite is always a sprite");
+        };
+        self.animator.apply(sprite);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(mode: PlayMode) -> SpriteSheet {
+        SpriteSheet::from_grid(Vertex2::new(10.0, 10.0), 2, 4, 1.0)
+            .with_clip("walk", &[0, 1, 2], mode)
+            .unwrap()
+    }
+
+    fn played_frames(animator: &mut Animator, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                animator.update(1.0);
+                animator.frame_index()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn from_grid() {
+        let sheet = SpriteSheet::from_grid(Vertex2::new(10.0, 20.0), 2, 3, 0.5);
+
+        assert_eq!(sheet.frames.len(), 3);
+        assert_eq!(
+            sheet.frames[2].source,
+            Dimensions::from_top_left(Vertex2::new(0.0, 20.0), 10.0, 20.0)
+        );
+        assert_eq!(sheet.frames[2].duration, 0.5);
+    }
+
+    #[test]
+    fn invalid_clips() {
+        let sheet = SpriteSheet::from_grid(Vertex2::new(10.0, 10.0), 2, 4, 1.0);
+
+        assert!(
+            sheet
+                .clone()
+                .with_clip("empty", &[], PlayMode::Loop)
+                .is_err()
+        );
+        assert!(
+            sheet
+                .with_clip("out_of_range", &[3, 4], PlayMode::Loop)
+                .is_err()
+        );
+
+        let sheet = SpriteSheet::from_grid(Vertex2::new(10.0, 10.0), 2, 4, 0.0);
+        assert!(sheet.with_clip("still", &[0, 1], PlayMode::Loop).is_err());
+    }
+
+    #[test]
+    fn empty_clip() {
+        let mut sheet = sheet(PlayMode::Loop);
+        sheet.clips.get_mut("walk").unwrap().frames.clear();
+        let mut animator = Animator::new(sheet);
+        animator.play("walk").unwrap();
+
+        assert!(animator.update(1.0).is_empty());
+        assert_eq!(animator.frame(), None);
+    }
+
+    #[test]
+    fn zero_duration_frames() {
+        let mut sheet = sheet(PlayMode::Loop);
+        sheet.clips.get_mut("walk").unwrap().frames[1].duration = 0.0;
+        let mut animator = Animator::new(sheet.clone());
+        animator.play("walk").unwrap();
+        assert_eq!(played_frames(&mut animator, 3), [2, 0, 2]);
+
+        for frame in &mut sheet.clips.get_mut("walk").unwrap().frames {
+            frame.duration = -1.0;
+        }
+        let mut animator = Animator::new(sheet);
+        animator.play("walk").unwrap();
+        assert!(animator.update(1.0).is_empty());
+        assert_eq!(animator.frame_index(), 0);
+    }
+
+    #[test]
+    fn play_loop() {
+        let mut animator = Animator::new(sheet(PlayMode::Loop));
+        animator.play("walk").unwrap();
+
+        assert_eq!(animator.frame_index(), 0);
+        assert_eq!(played_frames(&mut animator, 4), [1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn play_ping_pong() {
+        let mut animator = Animator::new(sheet(PlayMode::PingPong));
+        animator.play("walk").unwrap();
+
+        assert_eq!(played_frames(&mut animator, 6), [1, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn play_once() {
+        let mut animator = Animator::new(sheet(PlayMode::Once));
+        animator.play("walk").unwrap();
+
+        assert!(animator.update(2.0).is_empty());
+        assert_eq!(
+            animator.update(1.0),
+            [AnimationEvent::Finished("walk".to_owned())]
+        );
+        assert!(animator.is_finished());
+        assert!(animator.update(1.0).is_empty());
+        assert_eq!(animator.frame_index(), 2);
+    }
+
+    #[test]
+    fn loop_event() {
+        let mut animator = Animator::new(sheet(PlayMode::Loop));
+        animator.play("walk").unwrap();
+
+        assert!(animator.update(2.5).is_empty());
+        assert_eq!(
+            animator.update(0.5),
+            [AnimationEvent::Looped("walk".to_owned())]
+        );
+    }
+
+    #[test]
+    fn speed() {
+        let mut animator = Animator::new(sheet(PlayMode::Loop));
+        animator.play("walk").unwrap();
+        animator.set_speed(2.0);
+
+        animator.update(1.0);
+        assert_eq!(animator.frame_index(), 2);
+
+        animator.set_speed(-1.0);
+        assert_eq!(animator.speed(), 0.0);
+        animator.update(1.0);
+        assert_eq!(animator.frame_index(), 2);
+    }
+
+    #[test]
+    fn play_unknown_clip() {
+        let mut animator = Animator::new(sheet(PlayMode::Loop));
+        animator.play("walk").unwrap();
+        animator.update(1.0);
+
+        assert!(animator.play("run").is_err());
+        assert_eq!(animator.clip(), Some("walk"));
+        assert_eq!(animator.frame_index(), 1);
+    }
+
+    #[test]
+    fn play_keeps_current_clip() {
+        let mut animator = Animator::new(sheet(PlayMode::Loop));
+        animator.play("walk").unwrap();
+        animator.update(1.0);
+        animator.play("walk").unwrap();
+
+        assert_eq!(animator.frame_index(), 1);
+    }
+
+    #[test]
+    fn apply() {
+        let mut animator = Animator::new(sheet(PlayMode::Loop));
+        let mut sprite = Sprite::new(crate::ImageHandle::new(0, 0));
+        animator.play("walk").unwrap();
+        animator.update(1.0);
+        animator.apply(&mut sprite);
+
+        assert_eq!(sprite.source, Some(animator.sheet().frames[1].source));
+    }
+
+    #[test]
+    fn animated_sprite() {
+        let mut animator = Animator::new(sheet(PlayMode::Once));
+        animator.play("walk").unwrap();
+        let animated = AnimatedSprite::new(
+            Sprite::new(crate::ImageHandle::new(0, 0)),
+            animator,
+            Layer::One,
+        );
+        let frames = animated.animator.sheet().frames.clone();
+        assert_eq!(animated.sprite().source, Some(frames[0].source));
+
+        let mut engine = crate::TestEngine::new(vec![Box::new(animated)]);
+        engine.step(1.0).unwrap();
+        engine.step(1.0).unwrap();
+
+        let renderables = engine.components()[0].renderables();
+        assert!(matches!(
+            &renderables[0].shape,
+            Shape::Sprite(sprite) if sprite.source == Some(frames[2].source)
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+//! Importers for the JSON exported by Aseprite (<https://www.aseprite.org/docs/cli/#data>) and
+//! TexturePacker (the "JSON (Hash)", "JSON (Array)" and "PixiJS" formats) - both the hash and the
+//! array layouts of the `frames` are supported.
+
+use super::{Clip, Frame, PlayMode, SpriteSheet};
+use crate::{Vertex2, dimensions::Dimensions, error::Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(super) fn is_aseprite(json: &Value) -> bool {
+    let app = json["meta"]["app"].as_str().unwrap_or_default();
+    app.contains("aseprite")
+}
+
+impl SpriteSheet {
+    /// Every tag becomes a clip - the `forward`, `reverse`, `pingpong` and `pingpong_reverse`
+    /// directions are supported, and a tag with a `repeat` of `1` plays once.
+    pub fn from_aseprite(json: &Value) -> Result<Self> {
+        let frames = named_frames(json)?
+            .into_iter()
+            .map(|(name, frame)| {
+                let duration = frame["duration"]
+                    .as_f64()
+                    .ok_or_else(|| format!("The frame {name} has no duration"))?;
+                Ok(Frame {
+                    source: frame_source(&name, frame)?,
+                    duration: duration as f32 / 1000.0,
+                })
+            })
+            .collect::<Result<Vec<Frame>>>()?;
+
+        let mut clips = HashMap::default();
+        for tag in json["meta"]["frameTags"].as_array().into_iter().flatten() {
+            let name = tag["name"].as_str().ok_or("A frame tag has no name")?;
+            let from = index(&tag["from"], name)?;
+            let to = index(&tag["to"], name)?;
+            if from > to {
+                return Err(format!("The frame tag {name} has an empty range").into());
+            }
+            let mut clip_frames = frames
+                .get(from..=to)
+                .ok_or_else(|| format!("The frame tag {name} is out of range"))?
+                .to_vec();
+
+            let direction = tag["direction"].as_str().unwrap_or("forward");
+            if direction.ends_with("reverse") {
+                clip_frames.reverse();
+            }
+            let mode = if direction.starts_with("pingpong") {
+                PlayMode::PingPong
+            } else if tag["repeat"].as_str() == Some("1") {
+                PlayMode::Once
+            } else {
+                PlayMode::Loop
+            };
+
+            clips.insert(name.to_owned(), Clip::new(name, clip_frames, mode)?);
+        }
+
+        Ok(Self { frames, clips })
+    }
+
+    /// TexturePacker doesn't export durations, so every frame lasts `frame_duration` seconds.
+    ///
+    /// The clips come from the `animations` of the PixiJS format - for the other formats the
+    /// frames are grouped by their name without the trailing frame number and extension (e.g.
+    /// `walk_01.png` and `walk_02.png` become the `walk` clip). All clips loop.
+    pub fn from_texture_packer(json: &Value, frame_duration: f32) -> Result<Self> {
+        let named_frames = named_frames(json)?;
+        let mut frames = Vec::default();
+        let mut indices = HashMap::new();
+        for (index, (name, frame)) in named_frames.iter().enumerate() {
+            if frame["rotated"].as_bool() == Some(true) {
+                return Err(format!(
+                    "The frame {name} is rotated - rotated frames are not supported"
+                )
+                .into());
+            }
+
+            frames.push(Frame {
+                source: frame_source(name, frame)?,
+                duration: frame_duration,
+            });
+            indices.insert(name.as_str(), index);
+        }
+
+        let mut clips: HashMap<String, Clip> = HashMap::default();
+        if let Some(animations) = json["animations"].as_object() {
+            for (name, frame_names) in animations {
+                let clip_frames = frame_names
+                    .as_array()
+                    .ok_or_else(|| format!("The animation {name} is not a list of frames"))?
+                    .iter()
+                    .map(|frame_name| {
+                        let frame_name = frame_name.as_str().unwrap_or_default();
+                        indices
+                            .get(frame_name)
+                            .map(|index| frames[*index])
+                            .ok_or_else(|| {
+                                format!("The animation {name} has an unknown frame {frame_name}")
+                                    .into()
+                            })
+                    })
+                    .collect::<Result<Vec<Frame>>>()?;
+                clips.insert(name.clone(), Clip::new(name, clip_frames, PlayMode::Loop)?);
+            }
+        } else {
+            for ((name, _), frame) in named_frames.iter().zip(&frames) {
+                clips
+                    .entry(clip_name(name).to_owned())
+                    .or_insert_with(|| Clip {
+                        frames: Vec::default(),
+                        mode: PlayMode::Loop,
+                    })
+                    .frames
+                    .push(*frame);
+            }
+        }
+
+        Ok(Self { frames, clips })
+    }
+}
+
+/// The frames along with their names - in the order of the file.
+fn named_frames(json: &Value) -> Result<Vec<(String, &Value)>> {
+    match &json["frames"] {
+        Value::Object(frames) => Ok(frames
+            .iter()
+            .map(|(name, frame)| (name.clone(), frame))
+            .collect()),
+        Value::Array(frames) => Ok(frames
+            .iter()
+            .map(|frame| {
+                let name = frame["filename"].as_str().unwrap_or_default();
+                (name.to_owned(), frame)
+            })
+            .collect()),
+        _ => Err("The sprite sheet has no frames".into()),
+    }
+}
+
+fn frame_source(name: &str, frame: &Value) -> Result<Dimensions> {
+    let rect = &frame["frame"];
+    let field = |field: &str| {
+        rect[field]
+            .as_f64()
+            .map(|value| value as f32)
+            .ok_or_else(|| format!("The frame {name} has no {field}"))
+    };
+
+    Ok(Dimensions::from_top_left(
+        Vertex2::new(field("x")?, field("y")?),
+        field("w")?,
+        field("h")?,
+    ))
+}
+
+fn index(value: &Value, tag: &str) -> Result<usize> {
+    value
+        .as_u64()
+        .map(|index| index as usize)
+        .ok_or_else(|| format!("The frame tag {tag} has an invalid range").into())
+}
+
+/// `walk_01.png` -> `walk`
+fn clip_name(frame_name: &str) -> &str {
+    let name = frame_name
+        .rsplit_once('.')
+        .map_or(frame_name, |(name, _)| name);
+    name.trim_end_matches(|char: char| char.is_ascii_digit())
+        .trim_end_matches(['_', '-', ' '])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Asset;
+
+    const ASEPRITE: &str = r#"{
+        "frames": {
+            "player 0.aseprite": { "frame": { "x": 0, "y": 0, "w": 16, "h": 16 }, "duration": 100 },
+            "player 1.aseprite": { "frame": { "x": 16, "y": 0, "w": 16, "h": 16 }, "duration": 200 },
+            "player 2.aseprite": { "frame": { "x": 32, "y": 0, "w": 16, "h": 16 }, "duration": 100 },
+            "player 10.aseprite": { "frame": { "x": 48, "y": 0, "w": 16, "h": 16 }, "duration": 100 }
+        },
+        "meta": {
+            "app": "https://www.aseprite.org/",
+            "frameTags": [
+                { "name": "idle", "from": 0, "to": 1, "direction": "pingpong" },
+                { "name": "attack", "from": 1, "to": 3, "direction": "reverse", "repeat": "1" }
+            ]
+        }
+    }"#;
+
+    const TEXTURE_PACKER: &str = r#"{
+        "frames": [
+            { "filename": "walk_01.png", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "rotated": false },
+            { "filename": "walk_02.png", "frame": { "x": 8, "y": 0, "w": 8, "h": 8 }, "rotated": false },
+            { "filename": "jump.png", "frame": { "x": 0, "y": 8, "w": 8, "h": 16 }, "rotated": false }
+        ],
+        "meta": { "app": "https://www.codeandweb.com/texturepacker" }
+    }"#;
+
+    fn source(x: f32, y: f32, width: f32, height: f32) -> Dimensions {
+        Dimensions::from_top_left(Vertex2::new(x, y), width, height)
+    }
+
+    #[test]
+    fn aseprite() {
+        let sheet = SpriteSheet::decode(ASEPRITE.as_bytes().to_vec()).unwrap();
+
+        assert_eq!(sheet.frames.len(), 4);
+        assert_eq!(sheet.frames[1].duration, 0.2);
+        assert_eq!(sheet.frames[3].source, source(48.0, 0.0, 16.0, 16.0));
+
+        let idle = &sheet.clips["idle"];
+        assert_eq!(idle.mode, PlayMode::PingPong);
+        assert_eq!(idle.frames, sheet.frames[0..2]);
+
+        let attack = &sheet.clips["attack"];
+        assert_eq!(attack.mode, PlayMode::Once);
+        let sources: Vec<f32> = attack
+            .frames
+            .iter()
+            .map(|frame| frame.source.left())
+            .collect();
+        assert_eq!(sources, [48.0, 32.0, 16.0]);
+    }
+
+    #[test]
+    fn texture_packer() {
+        let sheet = SpriteSheet::decode(TEXTURE_PACKER.as_bytes().to_vec()).unwrap();
+
+        assert_eq!(sheet.frames.len(), 3);
+        assert_eq!(sheet.frames[2].source, source(0.0, 8.0, 8.0, 16.0));
+        assert_eq!(sheet.frames[2].duration, 0.1);
+        assert_eq!(sheet.clips["walk"].frames, sheet.frames[0..2]);
+        assert_eq!(sheet.clips["jump"].frames, sheet.frames[2..3]);
+    }
+
+    #[test]
+    fn texture_packer_animations() {
+        let mut json: Value = serde_json::from_str(TEXTURE_PACKER).unwrap();
+        json["animations"] = serde_json::json!({ "hop": ["jump.png", "walk_01.png"] });
+
+        let sheet = SpriteSheet::from_texture_packer(&json, 0.5).unwrap();
+        assert_eq!(sheet.clips.len(), 1);
+        assert_eq!(
+            sheet.clips["hop"].frames,
+            [sheet.frames[2], sheet.frames[0]]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let json = serde_json::json!({ "frames": { "a": { "frame": { "x": 0 } } } });
+        assert!(SpriteSheet::from_texture_packer(&json, 0.1).is_err());
+        assert!(SpriteSheet::from_aseprite(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn empty_clips() {
+        let mut aseprite: Value = serde_json::from_str(ASEPRITE).unwrap();
+        aseprite["meta"]["frameTags"][0]["from"] = 2.into();
+        assert!(SpriteSheet::from_aseprite(&aseprite).is_err());
+
+        let mut texture_packer: Value = serde_json::from_str(TEXTURE_PACKER).unwrap();
+        texture_packer["animations"] = serde_json::json!({ "hop": [] });
+        assert!(SpriteSheet::from_texture_packer(&texture_packer, 0.1).is_err());
+    }
+
+    #[test]
+    fn clip_names() {
+        assert_eq!(clip_name("walk_01.png"), "walk");
+        assert_eq!(clip_name("run-2"), "run");
+        assert_eq!(clip_name("player 10.aseprite"), "player");
+        assert_eq!(clip_name("jump.png"), "jump");
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::Vertex2;
 
-#[derive(Debug)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Dimensions {
     position: Vertex2<f32>,
     width: f32,
@@ -20,6 +20,29 @@ impl Dimensions {
         }
     }
 
+    /// Creates the dimensions from the top left corner (e.g. for rectangles in image pixels)
+    /// instead of the center.
+    pub fn from_top_left(top_left: Vertex2<f32>, width: f32, height: f32) -> Self {
+        Self::new(
+            Vertex2::new(top_left.x + width / 2.0, top_left.y + height / 2.0),
+            width,
+            height,
+        )
+    }
+
+    /// The center
+    pub fn position(&self) -> Vertex2<f32> {
+        self.position
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
     pub fn half_width(&self) -> f32 {
         self.width / 2.0
     }
@@ -71,6 +94,16 @@ mod tests {
         assert_eq!(dimensions.half_height(), HEIGHT / 2.0);
     }
 
+    #[test]
+    fn from_top_left() {
+        let dimensions = Dimensions::from_top_left(POSITION, WIDTH, HEIGHT);
+
+        assert_eq!(dimensions.left(), POS_X);
+        assert_eq!(dimensions.top(), POS_Y);
+        assert_eq!(dimensions.width(), WIDTH);
+        assert_eq!(dimensions.height(), HEIGHT);
+    }
+
     #[test]
     fn sides() {
         let dimensions = Dimensions::new(POSITION, WIDTH, HEIGHT);
@@ -1,33 +1,89 @@
 mod animation_frame;
 mod canvas;
+mod render_queue;
 mod renderer;
+mod window_size;
 
+pub mod asset;
+pub mod camera;
 pub mod component;
+pub mod error_policy;
 pub mod input;
-
-use crate::{Layer, Transform, Vertex2, engine::canvas::Canvas, error::Result};
-use component::Component;
-use input::Input;
+pub mod render_backend;
+pub mod screen;
+pub mod test_engine;
+pub mod time;
+pub mod viewport;
+
+use crate::{
+    Affine2, Vertex2,
+    dimensions::Dimensions,
+    engine::canvas::Canvas,
+    error::{Error, Result},
+    wasm_assert,
+};
+use animation_frame::{AnimationLoop, LoopState, WindowScheduler};
+use asset::{AssetLoader, AssetManager, FetchLoader};
+use camera::Camera;
+use component::{Component, text::Text};
+use error_policy::{ErrorAction, ErrorPolicy};
+use input::{Input, event_listener::EventListener};
+use log::error;
+use render_backend::RenderBackend;
+use render_queue::RenderQueue;
+use screen::{Scaling, Screen, ScreenLayout};
 use std::{
     cell::{RefCell, RefMut},
     rc::Rc,
 };
-use web_sys::{CanvasRenderingContext2d, Window};
-use web_time::{Duration, SystemTime}; // std::time::SystemTime panics in WASM
+use time::{DEFAULT_MAX_DELTA_TIME, FixedTimestep, Time};
+use viewport::Viewport;
+use web_sys::Window;
+use web_time::Instant; // std::time::Instant panics in WASM
+use window_size::WindowSize;
 
 /// The context is passed to the `update` callback of [Component](Component)
 ///
 /// It's use is to:
 /// - read mouse/keyboard inputs
-/// - provide the delta time value
+/// - provide the delta time value and the rest of the [Time](Time) (e.g. to slow it down)
+/// - read the size of the screen and whether it changed
+/// - load assets
+/// - move the cameras of the viewports and convert between screen and world coordinates
+/// - measure text
 pub struct Context<'a> {
     pub input: &'a Input,
+    pub assets: &'a mut AssetManager,
+    pub time: &'a mut Time,
+
+    /// Never empty - see [remove_viewport](Self::remove_viewport)
+    viewports: &'a mut Vec<Viewport>,
+    backend: &'a dyn RenderBackend,
+    layout: &'a ScreenLayout,
+    resized: bool,
     delta_time: f32,
 }
 
 impl<'a> Context<'a> {
-    pub fn new(input: &'a Input, delta_time: f32) -> Self {
-        Self { input, delta_time }
+    pub fn new(
+        input: &'a Input,
+        assets: &'a mut AssetManager,
+        viewports: &'a mut Vec<Viewport>,
+        backend: &'a dyn RenderBackend,
+        layout: &'a ScreenLayout,
+        resized: bool,
+        time: &'a mut Time,
+    ) -> Self {
+        Self {
+            input,
+            assets,
+            viewports,
+            delta_time: time.delta_time(),
+            time,
+            backend,
+            layout,
+            resized,
+        }
     }
 
     /// This is the number of seconds that passed since the last frame in the main loop.
@@ -43,9 +99,100 @@ impl<'a> Context<'a> {
     ///
     /// Let's say player has to move with a speed of `50`.
     /// The calculation for the movement  `50 * delta_time`.
+    ///
+    /// > **Note:** In [fixed_update](crate::ComponentLifecycle::fixed_update) it's the fixed step
+    /// > of the [FixedTimestep](FixedTimestep).
     pub fn delta_time(&self) -> f32 {
         self.delta_time
     }
+
+    /// How far the frame is between the last two fixed steps - from `0.0` (at the last step) to
+    /// `1.0` (at the next step). Use it in `update` to interpolate between the states of the last
+    /// two steps for smooth rendering - see [FixedTimestep](FixedTimestep).
+    ///
+    /// It's always `1.0` (the latest state) unless the engine is set up with a fixed timestep.
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.time.interpolation_alpha()
+    }
+
+    /// The size of the screen in logical pixels - see [Scaling](Scaling).
+    pub fn screen_size(&self) -> Vertex2<f32> {
+        self.layout.logical_size
+    }
+
+    /// How the logical pixels map onto the window - see [Scaling](Scaling).
+    pub fn screen_layout(&self) -> &ScreenLayout {
+        self.layout
+    }
+
+    /// Whether the size of the screen changed since the last frame (it's `true` for the first
+    /// frame as well) - use it to update anything that depends on the size.
+    pub fn resized(&self) -> bool {
+        self.resized
+    }
+
+    /// The bounding box of the text relative to the position of its renderable - see
+    /// [Text::measure].
+    pub fn measure_text(&self, text: &Text) -> Dimensions {
+        text.measure(self.backend)
+    }
+
+    /// The scene is rendered once per viewport - there's a single viewport covering the entire
+    /// canvas unless the engine is set up (or a component adds) more.
+    pub fn viewports(&self) -> &[Viewport] {
+        self.viewports
+    }
+
+    pub fn viewports_mut(&mut self) -> &mut [Viewport] {
+        self.viewports
+    }
+
+    /// Adds a viewport that's rendered after (on top of) the others and returns its index.
+    pub fn add_viewport(&mut self, viewport: Viewport) -> usize {
+        self.viewports.push(viewport);
+        self.viewports.len() - 1
+    }
+
+    /// Removes the viewport at the index - the viewports after it move down by one, so update
+    /// the [ViewportMask](viewport::ViewportMask)s that refer to them. The last remaining viewport can't be
+    /// removed, in which case (or if the index is out of range) `None` is returned.
+    pub fn remove_viewport(&mut self, index: usize) -> Option<Viewport> {
+        if self.viewports.len() <= 1 || index >= self.viewports.len() {
+            return None;
+        }
+        Some(self.viewports.remove(index))
+    }
+
+    /// The camera of the first viewport - the only camera unless the engine is set up with more
+    /// viewports.
+    pub fn camera(&self) -> &Camera {
+        &self.viewports[0].camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.viewports[0].camera
+    }
+
+    /// The index of the viewport under the point in screen pixels (e.g. the mouse position).
+    pub fn viewport_at(&self, point: Vertex2<f32>) -> Option<usize> {
+        self.viewports
+            .iter()
+            .position(|viewport| viewport.contains(point))
+    }
+
+    /// Converts a point in screen pixels (e.g. the mouse position) to world coordinates through
+    /// the camera of the viewport under the point (or the first viewport if there's none) - see
+    /// [Camera](Camera).
+    pub fn screen_to_world(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        let viewport = self.viewport_at(point).unwrap_or_default();
+        self.viewports[viewport].screen_to_world(point)
+    }
+
+    /// Converts a point in world coordinates to screen pixels through the camera of the first
+    /// viewport - see [Viewport::world_to_screen](Viewport::world_to_screen) for the others.
+    pub fn world_to_screen(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        self.viewports[0].world_to_screen(point)
+    }
 }
 
 /// This is a separate `State` struct as opposed to flattening its fields in the `Engine` struct
@@ -53,10 +200,20 @@ impl<'a> Context<'a> {
 /// behind an `Rc`. It's a lot more ergonomic to use the `Rc` once on the entire shared state as
 /// opposed to repeating it for each field.
 struct State {
-    canvas: Canvas,
+    canvas: RefCell<Canvas>,
     components: RefCell<Vec<Box<dyn Component>>>,
     input: RefCell<Input>,
-    last_time: RefCell<SystemTime>,
+    assets: RefCell<AssetManager>,
+    viewports: RefCell<Vec<Viewport>>,
+    scaling: RefCell<Scaling>,
+    screen: RefCell<Screen>,
+    window_size: WindowSize,
+    time: RefCell<Time>,
+    last_time: RefCell<Instant>,
+    error_policy: RefCell<ErrorPolicy>,
+
+    /// The handlers of the input and window events - detached when they're dropped
+    listeners: RefCell<Vec<EventListener>>,
 }
 
 /// This is the entry point of our application - initialize a logger, pass a collection of
@@ -76,7 +233,7 @@ struct State {
 ///
 ///     let components = Vec::default(); // Add components here
 ///     let engine = Engine::new(canvas_id, components)?;
-///     engine.run()?;
+///     engine.run()?; // Keeps running after the handle is dropped
 ///
 ///     Ok(())
 /// }
@@ -87,7 +244,7 @@ pub struct Engine {
 }
 
 impl Engine {
-    pub fn new(canvas_id: &str, components: Vec<Box<dyn Component>>) -> Result<Self> {
+    pub fn new(canvas_id: &str, mut components: Vec<Box<dyn Component>>) -> Result<Self> {
         let not_found_msg = |entity: &str| format!("Did not find '{}'", entity);
         let window = web_sys::window().ok_or_else(|| not_found_msg("window"))?;
 
@@ -100,13 +257,31 @@ impl Engine {
         };
 
         let input = Input::default();
-        input.init(&window)?;
+        let window_size = WindowSize::default();
+        let mut listeners = input.init(&window)?;
+        listeners.push(window_size.init(&window)?);
+
+        Engine::propagate_transforms(
+            &mut Engine::as_mut_components(&mut components),
+            &Affine2::IDENTITY,
+        );
+
+        let mut time = Time::default();
+        time.set_max_delta_time(Some(DEFAULT_MAX_DELTA_TIME));
 
         let state = State {
-            canvas,
+            canvas: RefCell::new(canvas),
             components: RefCell::new(components),
             input: RefCell::new(input),
-            last_time: RefCell::new(SystemTime::now()),
+            assets: RefCell::new(AssetManager::new(Box::new(FetchLoader::default()))),
+            viewports: RefCell::new(vec![Viewport::default()]),
+            scaling: RefCell::new(Scaling::default()),
+            screen: RefCell::new(Screen::default()),
+            window_size,
+            time: RefCell::new(time),
+            last_time: RefCell::new(Instant::now()),
+            error_policy: RefCell::new(ErrorPolicy::default()),
+            listeners: RefCell::new(listeners),
         };
 
         Ok(Self {
@@ -115,137 +290,718 @@ impl Engine {
         })
     }
 
-    pub fn run(&self) -> Result<()> {
-        let window = self.window.clone();
-        let state = self.state.clone();
-        animation_frame::request_recursive(
-            self.window.clone(),
-            Rc::new(move || Engine::main_loop(state.clone(), &window)),
-        )
+    /// Replaces the default loader (a [FetchLoader](FetchLoader) that loads assets relative to
+    /// the page).
+    pub fn with_asset_loader(self, loader: Box<dyn AssetLoader>) -> Self {
+        *self.state.assets.borrow_mut() = AssetManager::new(loader);
+        self
+    }
+
+    /// Replaces the default camera, which shows the world as is - the camera can be changed
+    /// later via the [Context](Context) as well.
+    pub fn with_camera(self, camera: Camera) -> Self {
+        self.with_viewports(vec![Viewport::full_screen(camera)])
+    }
+
+    /// Replaces the default viewport, which covers the entire canvas - e.g. with
+    /// [Viewport::split](Viewport::split) for split screens.
+    pub fn with_viewports(self, viewports: Vec<Viewport>) -> Self {
+        wasm_assert!(!viewports.is_empty());
+        *self.state.viewports.borrow_mut() = viewports;
+        self
+    }
+
+    /// Replaces the default scaling policy ([Scaling::Native](Scaling::Native)).
+    pub fn with_scaling(self, scaling: Scaling) -> Self {
+        *self.state.scaling.borrow_mut() = scaling;
+        self
+    }
+
+    /// Enables the [fixed_update](crate::ComponentLifecycle::fixed_update) of the components -
+    /// see [FixedTimestep](FixedTimestep).
+    pub fn with_fixed_timestep(self, fixed_timestep: FixedTimestep) -> Self {
+        self.state
+            .time
+            .borrow_mut()
+            .set_fixed_timestep(Some(fixed_timestep));
+        self
     }
 
-    fn main_loop(state: Rc<State>, window: &Window) -> Result<()> {
-        // TODO: Resize on resize event to avoid WASM boundary crossing on the hot path
-        let window_size = get_window_inner_size(window)?;
-        state.canvas.resize(window_size);
-        state.canvas.clear();
+    /// Replaces the default error policy ([ErrorPolicy::Log](ErrorPolicy::Log)).
+    pub fn with_error_policy(self, error_policy: ErrorPolicy) -> Self {
+        *self.state.error_policy.borrow_mut() = error_policy;
+        self
+    }
 
-        // Scope the immutable input borrow to avoid crashing on the
-        // mutable borrow afterwards.
+    /// Starts the main loop - it runs on every animation frame until it's stopped through the
+    /// returned [EngineHandle](EngineHandle) or by the [ErrorPolicy](ErrorPolicy).
+    pub fn run(self) -> Result<EngineHandle> {
+        *self.state.last_time.borrow_mut() = Instant::now();
+
+        let loop_state = self.state.clone();
+        let error_state = self.state.clone();
+        let teardown_state = self.state.clone();
+        let animation_loop = AnimationLoop::new(
+            Box::new(WindowScheduler::new((*self.window).clone())),
+            Box::new(move || Engine::main_loop(&loop_state)),
+            Box::new(move |err| Engine::handle_error(&error_state, err)),
+            Box::new(move || {
+                teardown_state.listeners.borrow_mut().clear();
+                teardown_state.components.borrow_mut().clear();
+            }),
+        );
+        // A failing request has already been handed to the error policy and stopped the loop
+        animation_loop.start()?;
+
+        Ok(EngineHandle {
+            state: self.state,
+            animation_loop,
+        })
+    }
+
+    fn main_loop(state: &State) -> Result<()> {
+        let delta_time = Engine::calc_delta_and_update_last(state.last_time.borrow_mut());
+        Engine::tick(state, delta_time)
+    }
+
+    fn handle_error(state: &State, err: &Error) -> ErrorAction {
+        state
+            .error_policy
+            .borrow_mut()
+            .handle(err, &mut *state.canvas.borrow_mut())
+    }
+
+    /// Runs a frame of the [Engine](Engine) that's `delta_time` seconds after the previous one.
+    fn tick(state: &State, delta_time: f32) -> Result<()> {
+        // Resizing the canvas resets its state, so it's only done when the layout changes
+        let mut screen = state.screen.borrow_mut();
+        let mut canvas = state.canvas.borrow_mut();
+        let layout = state
+            .window_size
+            .take()
+            .map(|(size, ratio)| ScreenLayout::new(*state.scaling.borrow(), size, ratio));
+        if screen.update(layout) {
+            canvas.resize(screen.layout())?;
+        }
+
+        let mut time = state.time.borrow_mut();
+        time.advance(delta_time);
+        Engine::frame(
+            &mut state.components.borrow_mut(),
+            &mut state.input.borrow_mut(),
+            &mut state.assets.borrow_mut(),
+            &mut state.viewports.borrow_mut(),
+            &mut *canvas,
+            &screen,
+            &mut time,
+        )
+    }
+
+    /// Runs a single iteration of the main loop - shared between the [Engine](Engine) and the
+    /// [TestEngine](test_engine::TestEngine).
+    ///
+    /// The frame is finished even if a component fails - the first error is returned afterwards.
+    fn frame(
+        components: &mut [Box<dyn Component>],
+        input: &mut Input,
+        assets: &mut AssetManager,
+        viewports: &mut Vec<Viewport>,
+        backend: &mut dyn RenderBackend,
+        screen: &Screen,
+        time: &mut Time,
+    ) -> Result<()> {
+        let fixed_timestep = time.fixed_timestep().copied();
+        let fixed_steps = time.fixed_steps();
+
+        let layout = screen.layout();
+        backend.clear();
+        assets.update(backend);
+        Engine::layout_viewports(viewports, layout.logical_size);
+        input.mouse.set_layout(*layout);
+
+        let mut result = Ok(());
         {
-            let delta_time = Engine::calc_delta_and_update_last(state.last_time.borrow_mut());
-            let input_borrow = state.input.borrow();
-            let mut ctx = Context::new(&input_borrow, delta_time);
+            let mut ctx = Context::new(
+                input,
+                assets,
+                viewports,
+                backend,
+                layout,
+                screen.resized(),
+                time,
+            );
+            let mut components = Engine::as_mut_components(components);
+
+            if let Some(fixed_timestep) = fixed_timestep {
+                ctx.delta_time = fixed_timestep.step;
+                for _ in 0..fixed_steps {
+                    let fixed_result = Engine::update_components(
+                        components.as_mut_slice(),
+                        &mut ctx,
+                        |cmp, ctx| cmp.try_fixed_update(ctx),
+                    );
+                    Engine::keep_first_error(&mut result, fixed_result);
+                    Engine::propagate_transforms(components.as_mut_slice(), &Affine2::IDENTITY);
+                }
+                ctx.delta_time = ctx.time.delta_time();
+            }
 
-            let mut components = state.components.borrow_mut();
-            let mut components: Vec<&mut dyn Component> =
-                components.iter_mut().map(|cmp| cmp.as_mut() as _).collect();
-            Engine::update_components(components.as_mut_slice(), &mut ctx, &Transform::default());
+            let update_result =
+                Engine::update_components(components.as_mut_slice(), &mut ctx, |cmp, ctx| {
+                    cmp.try_update(ctx)
+                });
+            Engine::keep_first_error(&mut result, update_result);
+            Engine::propagate_transforms(components.as_mut_slice(), &Affine2::IDENTITY);
+        }
+
+        // Viewports can be added or moved by the components
+        Engine::layout_viewports(viewports, layout.logical_size);
+        for viewport in viewports.iter_mut() {
+            viewport.camera.update(time.delta_time());
         }
 
-        let components = state.components.borrow();
         let components: Vec<&dyn Component> =
             components.iter().map(|cmp| cmp.as_ref() as _).collect();
-        Engine::render_layers(components.as_slice(), state.canvas.context());
+        RenderQueue::new(components.as_slice()).draw(backend, viewports, layout);
+
+        input.transition_states();
+        result
+    }
 
-        state.input.borrow_mut().transition_states();
+    fn layout_viewports(viewports: &mut [Viewport], canvas_size: Vertex2<f32>) {
+        for viewport in viewports {
+            viewport.set_canvas_size(canvas_size);
+        }
+    }
 
-        Ok(())
+    fn as_mut_components(components: &mut [Box<dyn Component>]) -> Vec<&mut dyn Component> {
+        components.iter_mut().map(|cmp| cmp.as_mut() as _).collect()
     }
 
-    fn calc_delta_and_update_last(mut last_time: RefMut<SystemTime>) -> f32 {
-        let elapsed = last_time.elapsed().unwrap_or_else(|_| Duration::default());
-        let delta_time = elapsed.as_millis() as f32 / 1000.0;
+    fn calc_delta_and_update_last(mut last_time: RefMut<Instant>) -> f32 {
+        let now = Instant::now();
+        let delta_time = now.duration_since(*last_time).as_secs_f32();
 
-        *last_time = SystemTime::now();
+        *last_time = now;
 
         delta_time
     }
 
+    /// Runs the `update` (or `fixed_update`) of the components.
     fn update_components(
         components: &mut [&mut dyn Component],
         ctx: &mut Context,
-        parent_transform: &Transform,
-    ) {
-        let mut ctx = Context::new(ctx.input, ctx.delta_time());
-
+        update: fn(&mut dyn Component, &mut Context) -> Result<()>,
+    ) -> Result<()> {
+        // A failing component doesn't stop the others from updating - the first error is kept
+        //
         // Children must be updated first so that parent components can have the final say in the
         // children's state (since the parents are responsible for the management).
         // Otherwise a child's state in the current frame can get modified by the parent state in
         // the next frame and we would get jittery movement.
+        let mut result = Ok(());
         for component in components.iter_mut() {
-            // NOTE: We can avoid clone by providing a one-shot split method.
-            let transform = parent_transform.clone() + component.transform();
             let mut children = component.children_mut();
-            Engine::update_components(children.as_mut_slice(), &mut ctx, &transform);
+            let children_result = Engine::update_components(children.as_mut_slice(), ctx, update);
+            Engine::keep_first_error(&mut result, children_result);
         }
 
         for component in components.iter_mut() {
-            component.transform_mut().parent = Some(Box::new(parent_transform.clone()));
-            component.update(&mut ctx);
-        }
-    }
-
-    fn render_layers(components: &[&dyn Component], render_ctx: &CanvasRenderingContext2d) {
-        Engine::render_components(components, render_ctx, Layer::Five, &Transform::default());
-        Engine::render_components(components, render_ctx, Layer::Four, &Transform::default());
-        Engine::render_components(components, render_ctx, Layer::Three, &Transform::default());
-        Engine::render_components(components, render_ctx, Layer::Two, &Transform::default());
-        Engine::render_components(components, render_ctx, Layer::One, &Transform::default());
-    }
-
-    fn render_components(
-        components: &[&dyn Component],
-        render_ctx: &CanvasRenderingContext2d,
-        layer: Layer,
-        parent_transform: &Transform,
-    ) {
-        for component in components.iter() {
-            let layer_filtered_renderables = component
-                .renderables()
-                .iter()
-                .filter(|renderable| renderable.layer == layer);
-
-            let renderable_parent_transform = parent_transform.clone() + component.transform();
-            for renderable in layer_filtered_renderables {
-                let transform = renderable_parent_transform.clone() + &renderable.transform;
-                renderer::render(render_ctx, &renderable.vertices, &transform);
-                (renderable.style)(render_ctx);
+            let component_result = update(*component, ctx);
+            Engine::keep_first_error(&mut result, component_result);
+        }
+        result
+    }
+
+    /// Only the first error of a frame is returned (and handed to the error policy) - the later
+    /// ones are logged, so they don't go unnoticed.
+    fn keep_first_error(result: &mut Result<()>, next: Result<()>) {
+        if let Err(err) = next {
+            match result {
+                Ok(()) => *result = Err(err),
+                Err(_) => error!("{}", err),
             }
         }
+    }
 
-        for component in components.iter() {
-            let children = component.children();
-            let children: Vec<&dyn Component> = children.iter().map(|child| &**child).collect();
-            let parent_transform = parent_transform.clone() + component.transform();
-            Engine::render_components(&children, render_ctx, layer, &parent_transform);
+    /// Hands every component the absolute matrix of its parent - the whole tree is walked every
+    /// time, but a transform only recalculates its absolute matrix when its local fields or the
+    /// matrix of its parent changed.
+    fn propagate_transforms(components: &mut [&mut dyn Component], parent_matrix: &Affine2) {
+        for component in components.iter_mut() {
+            component.transform_mut().set_parent_matrix(*parent_matrix);
+            let matrix = component.transform().absolute_matrix();
+
+            let mut children = component.children_mut();
+            Engine::propagate_transforms(children.as_mut_slice(), &matrix);
         }
     }
 }
 
-fn get_window_inner_size(window: &Window) -> Result<Vertex2<u32>> {
-    let width = window
-        .inner_width()
-        .map_err(|_| "Failed to get window's inner width")?
-        .as_f64()
-        .ok_or("Failed to convert window's inner width to f64")? as u32;
-
-    let height = window
-        .inner_height()
-        .map_err(|_| "Failed to get window's inner height")?
-        .as_f64()
-        .ok_or("Failed to convert window's inner width to f64")? as u32;
-
-    Ok(Vertex2 {
-        x: width,
-        y: height,
-    })
+/// Controls a running [Engine](Engine) - returned by [Engine::run](Engine::run).
+///
+/// Dropping the handle doesn't stop the engine, it keeps running until [stop](Self::stop) is
+/// called.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Engine, EngineHandle, Result};
+///
+/// // e.g. when the game is unmounted by a single page application
+/// fn mount(canvas_id: &str) -> Result<EngineHandle> {
+///     Engine::new(canvas_id, Vec::default())?.run()
+/// }
+///
+/// fn on_visibility_change(engine: &EngineHandle, hidden: bool) -> Result<()> {
+///     if hidden {
+///         engine.pause();
+///         Ok(())
+///     } else {
+///         engine.resume()
+///     }
+/// }
+///
+/// fn unmount(engine: EngineHandle) {
+///     engine.stop();
+/// }
+/// ```
+pub struct EngineHandle {
+    state: Rc<State>,
+    animation_loop: Rc<AnimationLoop>,
+}
+
+impl EngineHandle {
+    /// Cancels the pending frame - nothing is updated or rendered until the engine is resumed.
+    pub fn pause(&self) {
+        self.animation_loop.pause();
+    }
+
+    /// The time spent paused isn't counted towards the delta time of the next frame - fails if
+    /// the engine is stopped.
+    pub fn resume(&self) -> Result<()> {
+        if self.animation_loop.is_running() {
+            return Ok(());
+        }
+
+        *self.state.last_time.borrow_mut() = Instant::now();
+        self.animation_loop.start()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.animation_loop.state() == LoopState::Paused
+    }
+
+    /// Whether the engine was stopped by its [ErrorPolicy](ErrorPolicy) - it can't be resumed or
+    /// stepped anymore.
+    pub fn is_stopped(&self) -> bool {
+        self.animation_loop.state() == LoopState::Stopped
+    }
+
+    /// Runs a single frame of a paused engine (e.g. for frame by frame debugging) - the frame
+    /// lasts one fixed step, or 1/60 of a second without a [FixedTimestep](FixedTimestep).
+    ///
+    /// Like every other frame it's scaled by the [time scale](Time::time_scale) - with a time
+    /// scale of `0` the components see no time passing and only the rendering is refreshed.
+    ///
+    /// The errors of the frame are returned instead of being handed to the
+    /// [ErrorPolicy](ErrorPolicy) - fails without running a frame if the engine isn't paused.
+    pub fn step_once(&self) -> Result<()> {
+        let delta_time = self
+            .state
+            .time
+            .borrow()
+            .fixed_timestep()
+            .map_or(1.0 / 60.0, |fixed| fixed.step);
+        self.animation_loop
+            .step(|| Engine::tick(&self.state, delta_time))
+    }
+
+    /// Stops the engine for good - the pending frame is cancelled, the input and window event
+    /// handlers are detached and the components are dropped.
+    pub fn stop(self) {
+        self.animation_loop.stop();
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::{
+        ComponentLifecycle, Layer, Renderable, Style, Transform, ViewportMask, shape_factory,
+    };
+    use render_backend::{DrawCommand, RecordingBackend};
+    use test_engine::TestEngine;
+    use twors_derive::Component;
+
+    #[derive(Component)]
+    struct Square {
+        transform: Transform,
+        renderables: Vec<Renderable>,
+    }
+
+    impl Square {
+        fn new(layer: Layer, style: Style) -> Self {
+            Self {
+                transform: Transform::default(),
+                renderables: vec![
+                    Renderable::new(shape_factory::square(10.0), layer).with_style(style),
+                ],
+            }
+        }
+    }
+
+    impl ComponentLifecycle for Square {
+        fn update(&mut self, _: &mut Context) {}
+    }
+
+    #[derive(Component)]
+    struct Group {
+        transform: Transform,
+        renderables: Vec<Renderable>,
+        #[children]
+        children: Vec<Square>,
+    }
+
+    impl ComponentLifecycle for Group {
+        fn update(&mut self, _: &mut Context) {}
+    }
+
+    #[derive(Component)]
+    struct Mover {
+        updates: usize,
+
+        transform: Transform,
+        renderables: Vec<Renderable>,
+    }
+
+    impl ComponentLifecycle for Mover {
+        fn update(&mut self, ctx: &mut Context) {
+            self.updates += 1;
+            if ctx.input.keyboard.is_down(crate::Key::D) {
+                self.transform.position.x += 10.0 * ctx.delta_time();
+            }
+        }
+    }
+
+    #[test]
+    fn step_updates_components() {
+        let mover = Mover {
+            updates: 0,
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        };
+        let mut engine = TestEngine::new(vec![Box::new(mover)]);
+
+        engine.step(1.0).unwrap();
+        engine.press_key(crate::Key::D);
+        engine.step_many(2, 0.5).unwrap();
+        engine.release_key(crate::Key::D);
+        engine.step(1.0).unwrap();
+
+        let position = engine.components()[0].transform().position;
+        assert_eq!(position, Vertex2::new(10.0, 0.0));
+        assert_eq!(engine.commands(), &[DrawCommand::Clear]);
+    }
+
+    #[derive(Component)]
+    struct Failing {
+        message: &'static str,
+
+        transform: Transform,
+        renderables: Vec<Renderable>,
+    }
+
+    impl ComponentLifecycle for Failing {
+        fn update(&mut self, _: &mut Context) {}
+
+        fn try_update(&mut self, _: &mut Context) -> Result<()> {
+            Err(self.message.into())
+        }
+    }
+
+    #[test]
+    fn failing_update_finishes_frame() {
+        let failing = Failing {
+            message: "Failed",
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        };
+        let mover = Mover {
+            updates: 0,
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        };
+        let mut engine = TestEngine::new(vec![Box::new(failing), Box::new(mover)]);
+
+        engine.press_key(crate::Key::D);
+        assert_eq!(engine.step(1.0), Err("Failed".into()));
+
+        // The components after the failing one are still updated and the frame is rendered
+        let position = engine.components()[1].transform().position;
+        assert_eq!(position, Vertex2::new(10.0, 0.0));
+        assert_eq!(engine.commands(), &[DrawCommand::Clear]);
+    }
+
     #[test]
-    fn test() {
-        // TODO: Write tests
+    fn first_error_of_frame() {
+        let failing = |message| Failing {
+            message,
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        };
+        let mut engine = TestEngine::new(vec![
+            Box::new(failing("First")),
+            Box::new(failing("Second")),
+        ]);
+
+        // The second error is logged
+        assert_eq!(engine.step(1.0), Err("First".into()));
+    }
+
+    #[test]
+    fn render_nested_scaled_components() {
+        let mut child = Square::new(Layer::One, Style::default());
+        child.transform.position = Vertex2::new(10.0, 0.0);
+        let group = Group {
+            transform: Transform {
+                scale: Vertex2::new(2.0, 2.0),
+                ..Transform::from_position(Vertex2::new(100.0, 100.0))
+            },
+            renderables: Vec::default(),
+            children: vec![child],
+        };
+
+        let mut group = group;
+        Engine::propagate_transforms(&mut [&mut group], &Affine2::IDENTITY);
+
+        let mut backend = RecordingBackend::default();
+        RenderQueue::new(&[&group]).draw(
+            &mut backend,
+            &[Viewport::default()],
+            &ScreenLayout::default(),
+        );
+
+        // The child is at (120, 100) and its top-left corner at (-5, -5) is scaled by the parent
+        assert_eq!(
+            backend.commands()[1],
+            DrawCommand::MoveTo(Vertex2::new(110.0, 90.0))
+        );
+    }
+
+    #[test]
+    fn render_layers_back_to_front() {
+        let top = Square::new(Layer::One, Style::filled("top"));
+        let bottom = Square::new(Layer::Five, Style::filled("bottom"));
+        let components: Vec<&dyn Component> = vec![&top, &bottom];
+
+        let mut backend = RecordingBackend::default();
+        RenderQueue::new(&components).draw(
+            &mut backend,
+            &[Viewport::default()],
+            &ScreenLayout::default(),
+        );
+
+        let styles: Vec<&DrawCommand> = backend
+            .commands()
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::SetFillStyle(_)))
+            .collect();
+        assert_eq!(
+            styles,
+            [
+                &DrawCommand::SetFillStyle("bottom".to_owned()),
+                &DrawCommand::SetFillStyle("top".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn camera_skips_screen_layers() {
+        let world = Square::new(Layer::Two, Style::default());
+        let hud = Square::new(Layer::One, Style::default());
+        let mut camera = Camera::default().with_screen_layer(Layer::One);
+        camera.position = Vertex2::new(100.0, 50.0);
+        let mut engine = TestEngine::new(vec![Box::new(world), Box::new(hud)]).with_camera(camera);
+        engine.step(0.0).unwrap();
+
+        let moves: Vec<&DrawCommand> = engine
+            .commands()
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::MoveTo(_)))
+            .collect();
+        assert_eq!(
+            moves,
+            [
+                &DrawCommand::MoveTo(Vertex2::new(-105.0, -55.0)),
+                &DrawCommand::MoveTo(Vertex2::new(-5.0, -5.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn context_viewports() {
+        let input = Input::default();
+        let mut assets = AssetManager::new(Box::new(crate::MemoryLoader::default()));
+        let mut viewports = vec![Viewport::default()];
+        let backend = RecordingBackend::default();
+        let layout = ScreenLayout::new(Scaling::default(), backend.size(), 1.0);
+        let mut time = Time::default();
+        let mut ctx = Context::new(
+            &input,
+            &mut assets,
+            &mut viewports,
+            &backend,
+            &layout,
+            false,
+            &mut time,
+        );
+
+        assert!(ctx.remove_viewport(0).is_none());
+        assert_eq!(ctx.add_viewport(Viewport::split(2, 1).remove(1)), 1);
+        assert!(ctx.remove_viewport(2).is_none());
+        assert!(ctx.remove_viewport(0).is_some());
+        assert_eq!(ctx.viewports().len(), 1);
+        ctx.viewports_mut()[0].camera.position = Vertex2::new(10.0, 0.0);
+        assert_eq!(ctx.camera().position, Vertex2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn split_screen_viewports() {
+        let world = Square::new(Layer::Two, Style::default());
+        let mut hud = Square::new(Layer::One, Style::default());
+        hud.renderables[0].viewports = ViewportMask::only(1);
+
+        // The HUD is masked to the second viewport and drawn relative to it
+        let mut viewports = Viewport::split(2, 1);
+        viewports[1].camera = Camera::default().with_screen_layer(Layer::One);
+        viewports[1].camera.position = Vertex2::new(100.0, 0.0);
+        let mut engine =
+            TestEngine::new(vec![Box::new(world), Box::new(hud)]).with_viewports(viewports);
+        engine.step(0.0).unwrap();
+
+        let commands: Vec<&DrawCommand> = engine
+            .commands()
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::MoveTo(_) | DrawCommand::Clip(_)))
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                &DrawCommand::Clip(Dimensions::from_top_left(
+                    Vertex2::new(0.0, 0.0),
+                    400.0,
+                    600.0
+                )),
+                &DrawCommand::MoveTo(Vertex2::new(-5.0, -5.0)),
+                &DrawCommand::Clip(Dimensions::from_top_left(
+                    Vertex2::new(400.0, 0.0),
+                    400.0,
+                    600.0
+                )),
+                &DrawCommand::MoveTo(Vertex2::new(295.0, -5.0)),
+                &DrawCommand::MoveTo(Vertex2::new(395.0, -5.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn letterboxed_scaling() {
+        let square = Square::new(Layer::One, Style::default());
+        let mut engine = TestEngine::new(vec![Box::new(square)])
+            .with_scaling(Scaling::Fixed(Vertex2::new(400.0, 400.0)));
+        engine.step(0.0).unwrap();
+
+        // The 800x600 window fits a 600x600 screen, with 100 pixel bars on both sides
+        let commands: Vec<&DrawCommand> = engine
+            .commands()
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::MoveTo(_) | DrawCommand::Clip(_)))
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                &DrawCommand::Clip(Dimensions::from_top_left(
+                    Vertex2::new(100.0, 0.0),
+                    600.0,
+                    600.0
+                )),
+                &DrawCommand::MoveTo(Vertex2::new(92.5, -7.5)),
+            ]
+        );
+    }
+
+    #[derive(Component)]
+    struct ResizeRecorder {
+        // The screen sizes of the frames that were resized
+        resizes: Rc<RefCell<Vec<Vertex2<f32>>>>,
+
+        transform: Transform,
+        renderables: Vec<Renderable>,
+    }
+
+    impl ComponentLifecycle for ResizeRecorder {
+        fn update(&mut self, ctx: &mut Context) {
+            if ctx.resized() {
+                self.resizes.borrow_mut().push(ctx.screen_size());
+            }
+        }
+    }
+
+    #[test]
+    fn resized_only_on_change() {
+        let resizes = Rc::new(RefCell::new(Vec::default()));
+        let recorder = ResizeRecorder {
+            resizes: resizes.clone(),
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        };
+        let mut engine =
+            TestEngine::new(vec![Box::new(recorder)]).with_scaling(Scaling::FitWidth(400.0));
+
+        engine.step_many(3, 0.1).unwrap();
+        engine.resize_window(Vertex2::new(800.0, 400.0));
+        engine.step_many(2, 0.1).unwrap();
+
+        // The first frame and the frame after the resize
+        assert_eq!(
+            *resizes.borrow(),
+            [Vertex2::new(400.0, 300.0), Vertex2::new(400.0, 200.0)]
+        );
+    }
+
+    #[derive(Component)]
+    struct Stepper {
+        transform: Transform,
+        renderables: Vec<Renderable>,
+    }
+
+    impl ComponentLifecycle for Stepper {
+        fn fixed_update(&mut self, ctx: &mut Context) {
+            self.transform.position.x += ctx.delta_time();
+        }
+
+        fn update(&mut self, ctx: &mut Context) {
+            self.transform.position.y = ctx.interpolation_alpha();
+        }
+    }
+
+    #[test]
+    fn fixed_timestep() {
+        let stepper = Stepper {
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        };
+        let mut engine =
+            TestEngine::new(vec![Box::new(stepper)]).with_fixed_timestep(FixedTimestep::new(8.0));
+
+        // Two steps of 0.125 seconds, with half a step left over
+        engine.step(0.3125).unwrap();
+        let position = engine.components()[0].transform().position;
+        assert_eq!(position, Vertex2::new(0.25, 0.5));
+
+        engine.step(0.0625).unwrap();
+        let position = engine.components()[0].transform().position;
+        assert_eq!(position, Vertex2::new(0.375, 0.0));
     }
 }
@@ -1,49 +1,426 @@
-use crate::error::Result;
-use std::{cell::RefCell, rc::Rc};
+use super::error_policy::ErrorAction;
+use crate::error::{Error, Result};
+use log::warn;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 use wasm_bindgen::{JsCast, prelude::Closure};
 use web_sys::{Window, js_sys::Function};
 
 pub type RequestAnimationFrameCallback = dyn Fn() -> Result<()>;
 
-pub fn request_recursive(
-    window: Rc<Window>,
-    callback: Rc<RequestAnimationFrameCallback>,
-) -> Result<()> {
-    // https://rustwasm.github.io/wasm-bindgen/examples/request-animation-frame.html
-    let callback_ref_alpha = Rc::new(RefCell::new(None));
-    let callback_ref_beta = callback_ref_alpha.clone();
-
-    let wasm_window = window;
-    let js_window = wasm_window.clone();
-    *callback_ref_alpha.borrow_mut() = Some(Closure::<dyn FnMut()>::new(move || {
-        match callback() {
-            Ok(_) => (),
-            Err(_error) => {
-                return;
+/// Called with the errors of the callback and of the animation frame requests.
+pub type AnimationLoopErrorCallback = dyn Fn(&Error) -> ErrorAction;
+
+/// Called once when the loop stops.
+pub type AnimationLoopTeardown = dyn FnOnce();
+
+/// Calls a callback on the requested animation frames - the browser's `requestAnimationFrame`
+/// outside of the tests.
+pub trait FrameScheduler {
+    /// Replaces the callback of the requested frames.
+    fn set_callback(&self, callback: Box<dyn FnMut()>);
+
+    /// Drops the callback - frames can't be requested afterwards.
+    fn clear_callback(&self);
+
+    /// Requests a single call of the callback - returns the id of the request.
+    fn request(&self) -> Result<i32>;
+
+    fn cancel(&self, request_id: i32) -> Result<()>;
+}
+
+/// Schedules the frames through the window.
+pub struct WindowScheduler {
+    window: Window,
+    closure: RefCell<Option<Closure<dyn FnMut()>>>,
+}
+
+impl WindowScheduler {
+    pub fn new(window: Window) -> Self {
+        Self {
+            window,
+            closure: RefCell::new(None),
+        }
+    }
+}
+
+impl FrameScheduler for WindowScheduler {
+    fn set_callback(&self, callback: Box<dyn FnMut()>) {
+        *self.closure.borrow_mut() = Some(Closure::wrap(callback));
+    }
+
+    fn clear_callback(&self) {
+        self.closure.borrow_mut().take();
+    }
+
+    fn request(&self) -> Result<i32> {
+        let closure = self.closure.borrow();
+        let Some(closure) = closure.as_ref() else {
+            return Err("The animation loop is stopped".into());
+        };
+
+        let closure = closure.as_ref().as_ref().unchecked_ref::<Function>();
+        let request_id = self
+            .window
+            .request_animation_frame(closure)
+            .map_err(|_| "Failed to request animation frame")?;
+        Ok(request_id)
+    }
+
+    fn cancel(&self, request_id: i32) -> Result<()> {
+        self.window
+            .cancel_animation_frame(request_id)
+            .map_err(|_| "Failed to cancel animation frame")?;
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoopState {
+    Paused,
+    Running,
+    /// Can't be started again
+    Stopped,
+}
+
+/// Calls the callback on every animation frame while it's running.
+///
+/// The callback passed to the scheduler owns the loop, so the loop keeps itself alive (without
+/// anyone holding on to it) until it's [stopped](Self::stop).
+pub struct AnimationLoop {
+    scheduler: Box<dyn FrameScheduler>,
+    on_error: Rc<AnimationLoopErrorCallback>,
+    teardown: RefCell<Option<Box<AnimationLoopTeardown>>>,
+
+    /// The id of the pending animation frame request
+    request_id: Cell<Option<i32>>,
+    state: Cell<LoopState>,
+}
+
+impl AnimationLoop {
+    /// The loop starts paused. It stops when `on_error` returns
+    /// [ErrorAction::Stop](ErrorAction::Stop), or when the next frame can't be requested.
+    pub fn new(
+        scheduler: Box<dyn FrameScheduler>,
+        callback: Box<RequestAnimationFrameCallback>,
+        on_error: Box<AnimationLoopErrorCallback>,
+        teardown: Box<AnimationLoopTeardown>,
+    ) -> Rc<Self> {
+        // https://rustwasm.github.io/wasm-bindgen/examples/request-animation-frame.html
+        let animation_loop = Rc::new(Self {
+            scheduler,
+            on_error: on_error.into(),
+            teardown: RefCell::new(Some(teardown)),
+            request_id: Cell::new(None),
+            state: Cell::new(LoopState::Paused),
+        });
+
+        let closure_loop = animation_loop.clone();
+        let on_error = animation_loop.on_error.clone();
+        animation_loop.scheduler.set_callback(Box::new(move || {
+            closure_loop.request_id.set(None);
+            if let Err(err) = callback()
+                && on_error(&err) == ErrorAction::Stop
+            {
+                closure_loop.stop();
             }
+
+            // The callback can pause or stop the loop
+            if closure_loop.is_running()
+                && let Err(err) = closure_loop.request()
+            {
+                on_error(&err);
+                closure_loop.stop();
+            }
+        }));
+
+        animation_loop
+    }
+
+    pub fn state(&self) -> LoopState {
+        self.state.get()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.get() == LoopState::Running
+    }
+
+    /// Requests the next animation frame - does nothing if the loop is already running.
+    ///
+    /// A failing request is handed to `on_error` and stops the loop, just like a failing request
+    /// within a frame.
+    pub fn start(&self) -> Result<()> {
+        match self.state.get() {
+            LoopState::Running => return Ok(()),
+            LoopState::Stopped => return Err(stopped()),
+            LoopState::Paused => (),
         }
 
-        let closure = callback_ref_beta.borrow();
-        let request_result = request_animation_frame(&js_window, closure.as_ref().unwrap());
+        if let Err(err) = self.request() {
+            (self.on_error)(&err);
+            self.stop();
+            return Err(err);
+        }
+        self.state.set(LoopState::Running);
+        Ok(())
+    }
 
-        match request_result {
-            Ok(_) => {}
-            Err(_error) => (),
+    /// Cancels the pending animation frame - the loop can be started again.
+    pub fn pause(&self) {
+        if self.state.get() != LoopState::Running {
+            return;
         }
-    }));
 
-    let closure = callback_ref_alpha.borrow();
-    request_animation_frame(&wasm_window, closure.as_ref().unwrap())?;
+        self.state.set(LoopState::Paused);
+        if let Some(request_id) = self.request_id.take()
+            && let Err(err) = self.scheduler.cancel(request_id)
+        {
+            warn!("{}", err);
+        }
+    }
 
-    // TODO: Block until an error is returned
-    Ok(())
+    /// Runs a single frame of a paused loop.
+    pub fn step(&self, frame: impl FnOnce() -> Result<()>) -> Result<()> {
+        match self.state.get() {
+            LoopState::Paused => frame(),
+            LoopState::Running => Err("The engine has to be paused to step it".into()),
+            LoopState::Stopped => Err(stopped()),
+        }
+    }
+
+    /// Pauses the loop for good, drops the callback along with everything it owns and runs the
+    /// teardown - does nothing if the loop is already stopped.
+    ///
+    /// > **Note:** it's safe to stop the loop from within the callback - the browser frees the
+    /// > closure once it returns.
+    pub fn stop(&self) {
+        if self.state.get() == LoopState::Stopped {
+            return;
+        }
+
+        self.pause();
+        self.state.set(LoopState::Stopped);
+        self.scheduler.clear_callback();
+        if let Some(teardown) = self.teardown.borrow_mut().take() {
+            teardown();
+        }
+    }
+
+    fn request(&self) -> Result<()> {
+        let request_id = self.scheduler.request()?;
+        self.request_id.set(Some(request_id));
+        Ok(())
+    }
+}
+
+fn stopped() -> Error {
+    "The engine is stopped - it can't be resumed or stepped".into()
 }
 
-fn request_animation_frame(window: &Window, closure: &Closure<dyn FnMut()>) -> Result<()> {
-    let closure = closure.as_ref().as_ref().unchecked_ref::<Function>();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The frames of a [FakeScheduler](FakeScheduler) - shared with the test.
+    #[derive(Default)]
+    struct Frames {
+        callback: RefCell<Option<Box<dyn FnMut()>>>,
+        cleared: Cell<bool>,
+        pending: Cell<Option<i32>>,
+        next_id: Cell<i32>,
+        fail: Cell<bool>,
+    }
+
+    impl Frames {
+        /// Runs the pending frame - returns whether there was one.
+        fn run(&self) -> bool {
+            if self.pending.take().is_none() {
+                return false;
+            }
+
+            // Taken out while it runs, since it can request, cancel or clear
+            let Some(mut callback) = self.callback.borrow_mut().take() else {
+                return false;
+            };
+            callback();
+            if !self.cleared.get() {
+                *self.callback.borrow_mut() = Some(callback);
+            }
+            true
+        }
+    }
+
+    struct FakeScheduler(Rc<Frames>);
+
+    impl FrameScheduler for FakeScheduler {
+        fn set_callback(&self, callback: Box<dyn FnMut()>) {
+            self.0.cleared.set(false);
+            *self.0.callback.borrow_mut() = Some(callback);
+        }
+
+        fn clear_callback(&self) {
+            self.0.cleared.set(true);
+            self.0.callback.borrow_mut().take();
+        }
+
+        fn request(&self) -> Result<i32> {
+            if self.0.cleared.get() || self.0.fail.get() {
+                return Err("Failed to request animation frame".into());
+            }
+
+            let request_id = self.0.next_id.get();
+            self.0.next_id.set(request_id + 1);
+            self.0.pending.set(Some(request_id));
+            Ok(request_id)
+        }
+
+        fn cancel(&self, request_id: i32) -> Result<()> {
+            if self.0.pending.get() == Some(request_id) {
+                self.0.pending.set(None);
+            }
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        frames: Rc<Frames>,
+        /// The number of times the callback ran
+        calls: Rc<Cell<usize>>,
+        errors: Rc<Cell<usize>>,
+        torn_down: Rc<Cell<usize>>,
+        animation_loop: Rc<AnimationLoop>,
+    }
+
+    fn fixture(result: fn() -> Result<()>, action: ErrorAction) -> Fixture {
+        let frames = Rc::new(Frames::default());
+        let calls = Rc::new(Cell::new(0));
+        let errors = Rc::new(Cell::new(0));
+        let torn_down = Rc::new(Cell::new(0));
+
+        let callback_calls = calls.clone();
+        let error_count = errors.clone();
+        let teardown_count = torn_down.clone();
+        let animation_loop = AnimationLoop::new(
+            Box::new(FakeScheduler(frames.clone())),
+            Box::new(move || {
+                callback_calls.set(callback_calls.get() + 1);
+                result()
+            }),
+            Box::new(move |_| {
+                error_count.set(error_count.get() + 1);
+                action
+            }),
+            Box::new(move || teardown_count.set(teardown_count.get() + 1)),
+        );
+
+        Fixture {
+            frames,
+            calls,
+            errors,
+            torn_down,
+            animation_loop,
+        }
+    }
+
+    #[test]
+    fn pause_step_resume_stop() {
+        let Fixture {
+            frames,
+            calls,
+            torn_down,
+            animation_loop,
+            ..
+        } = fixture(|| Ok(()), ErrorAction::Continue);
+        assert_eq!(animation_loop.state(), LoopState::Paused);
+
+        animation_loop.start().unwrap();
+        assert!(frames.run() && frames.run());
+        assert_eq!(calls.get(), 2);
+
+        animation_loop.pause();
+        assert!(!frames.run());
+        animation_loop.step(|| Ok(())).unwrap();
+
+        animation_loop.start().unwrap();
+        assert!(animation_loop.step(|| Ok(())).is_err());
+        assert!(frames.run());
+        assert_eq!(calls.get(), 3);
+
+        animation_loop.stop();
+        animation_loop.stop();
+        assert_eq!(animation_loop.state(), LoopState::Stopped);
+        assert_eq!(torn_down.get(), 1);
+        assert!(!frames.run());
+        assert!(animation_loop.start().is_err());
+        assert!(animation_loop.step(|| Ok(())).is_err());
+
+        // The callback owned the loop - it's dropped along with it
+        assert_eq!(Rc::strong_count(&calls), 1);
+        assert_eq!(Rc::strong_count(&animation_loop), 1);
+    }
+
+    #[test]
+    fn stop_on_error() {
+        let Fixture {
+            frames,
+            errors,
+            torn_down,
+            animation_loop,
+            ..
+        } = fixture(|| Err("Failed".into()), ErrorAction::Continue);
+        animation_loop.start().unwrap();
+        assert!(frames.run() && frames.run());
+        assert_eq!(errors.get(), 2);
+        assert!(animation_loop.is_running());
+
+        let Fixture {
+            frames,
+            errors,
+            torn_down: stop_torn_down,
+            animation_loop: stop_loop,
+            ..
+        } = fixture(|| Err("Failed".into()), ErrorAction::Stop);
+        stop_loop.start().unwrap();
+        assert!(frames.run());
+        assert_eq!(errors.get(), 1);
+        assert_eq!(stop_loop.state(), LoopState::Stopped);
+        assert_eq!(stop_torn_down.get(), 1);
+        assert!(stop_loop.start().is_err());
+        assert!(stop_loop.step(|| Ok(())).is_err());
+        assert_eq!(torn_down.get(), 0);
+    }
+
+    #[test]
+    fn failing_request() {
+        let Fixture {
+            frames,
+            errors,
+            torn_down,
+            animation_loop,
+            ..
+        } = fixture(|| Ok(()), ErrorAction::Continue);
+        frames.fail.set(true);
+
+        // The action doesn't matter - there's no pending frame to keep going with
+        assert!(animation_loop.start().is_err());
+        assert_eq!(errors.get(), 1);
+        assert_eq!(animation_loop.state(), LoopState::Stopped);
+        assert_eq!(torn_down.get(), 1);
 
-    window
-        .request_animation_frame(closure)
-        .map_err(|_| "Failed to request animation frame")?;
-    Ok(())
+        let Fixture {
+            frames,
+            errors,
+            torn_down,
+            animation_loop,
+            ..
+        } = fixture(|| Ok(()), ErrorAction::Continue);
+        animation_loop.start().unwrap();
+        frames.fail.set(true);
+        assert!(frames.run());
+        assert_eq!(errors.get(), 1);
+        assert_eq!(animation_loop.state(), LoopState::Stopped);
+        assert_eq!(torn_down.get(), 1);
+    }
 }
@@ -0,0 +1,428 @@
+//! Resources that arrive after the engine has started (images, JSON, fonts, audio, ...).
+//!
+//! Loads are started via the [AssetManager](AssetManager) (see `Context::assets`), which returns
+//! a typed [Handle](Handle) right away - the asset itself can be read once its
+//! [LoadState](LoadState) is `Ready`.
+
+mod fetch;
+mod image;
+mod loader;
+
+pub use fetch::FetchLoader;
+pub use image::{Image, ImageHandle, ImageLoader};
+pub use loader::{AssetLoader, FileLoader, LoadResult, MemoryLoader};
+
+use crate::error::Result;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+/// Refers to an asset of type `T` - it's cheap to copy and it's valid as soon as the load has
+/// started.
+///
+/// A handle only refers to an asset of the [AssetManager](AssetManager) that handed it out.
+pub struct Handle<T> {
+    /// The id of the manager that handed the handle out
+    manager: u32,
+    index: usize,
+    asset: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub(crate) const fn new(manager: u32, index: usize) -> Self {
+        Self {
+            manager,
+            index,
+            asset: PhantomData,
+        }
+    }
+}
+
+// Derives would needlessly require `T` to implement the traits as well
+impl<T> Copy for Handle<T> {}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.manager == other.manager && self.index == other.index
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.manager.hash(state);
+        self.index.hash(state);
+    }
+}
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}/{})", self.manager, self.index)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadState {
+    Loading,
+    Ready,
+    /// The reason of the failure
+    Failed(String),
+}
+
+/// A type that can be created from the bytes of a file.
+///
+/// Raw bytes (`Vec<u8>` - e.g. for fonts and audio), text (`String`) and JSON
+/// (`serde_json::Value`) are supported out of the box.
+pub trait Asset: Sized + 'static {
+    fn decode(bytes: Vec<u8>) -> Result<Self>;
+}
+
+impl Asset for Vec<u8> {
+    fn decode(bytes: Vec<u8>) -> Result<Self> {
+        Ok(bytes)
+    }
+}
+
+impl Asset for String {
+    fn decode(bytes: Vec<u8>) -> Result<Self> {
+        String::from_utf8(bytes).map_err(|_| "The file is not valid UTF-8".into())
+    }
+}
+
+impl Asset for serde_json::Value {
+    fn decode(bytes: Vec<u8>) -> Result<Self> {
+        serde_json::from_slice(&bytes).map_err(|err| format!("Invalid JSON: {err}").into())
+    }
+}
+
+/// The id of the next [AssetManager](AssetManager) - handles carry it, so that a manager doesn't
+/// mistake the handles of another one for its own.
+static NEXT_MANAGER_ID: AtomicU32 = AtomicU32::new(0);
+
+/// The state of a handle that the manager didn't hand out (e.g. it came from the manager that was
+/// replaced by `Engine::with_asset_loader`).
+static UNKNOWN_HANDLE: LazyLock<LoadState> =
+    LazyLock::new(|| LoadState::Failed("The handle belongs to another asset manager".into()));
+
+type Decoder = fn(Vec<u8>) -> Result<Box<dyn Any>>;
+
+enum Source {
+    /// Bytes from the [AssetLoader](AssetLoader) that are turned into the asset by the decoder
+    Loader(Decoder),
+    /// Images are loaded by the render backend
+    Image,
+}
+
+struct Entry {
+    path: String,
+    source: Source,
+    state: LoadState,
+    asset: Option<Box<dyn Any>>,
+}
+
+/// Starts loads, hands out typed [Handle](Handle)s and keeps the loaded assets.
+///
+/// Loading the same path as the same type twice returns the same handle, so it's fine to call
+/// `load` every frame instead of storing the handle. Loads finish between frames - the state
+/// of an asset never changes during an `update`.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::prelude::*;
+///
+/// fn update_loading_screen(ctx: &mut Context) -> bool {
+///     let _player = ctx.assets.load_image("assets/player.png");
+///     let _level = ctx.assets.load::<serde_json::Value>("assets/level.json");
+///
+///     let percent = ctx.assets.progress() * 100.0;
+///     log::info!("Loading... {percent:.0}%");
+///
+///     ctx.assets.is_finished()
+/// }
+/// ```
+pub struct AssetManager {
+    id: u32,
+    loader: Box<dyn AssetLoader>,
+    entries: Vec<Entry>,
+    handles: HashMap<(String, TypeId), usize>,
+
+    /// Images that have not been handed to the render backend yet
+    pending_images: Vec<ImageHandle>,
+}
+
+impl AssetManager {
+    pub fn new(loader: Box<dyn AssetLoader>) -> Self {
+        Self {
+            id: NEXT_MANAGER_ID.fetch_add(1, Ordering::Relaxed),
+            loader,
+            entries: Vec::default(),
+            handles: HashMap::default(),
+            pending_images: Vec::default(),
+        }
+    }
+
+    pub fn load<T: Asset>(&mut self, path: &str) -> Handle<T> {
+        let decoder: Decoder = |bytes| T::decode(bytes).map(|asset| Box::new(asset) as _);
+        let (index, is_new) = self.entry::<T>(path, Source::Loader(decoder));
+        if is_new && !self.is_requested(path, index) {
+            self.loader.start(path);
+        }
+        Handle::new(self.id, index)
+    }
+
+    pub fn load_image(&mut self, path: &str) -> ImageHandle {
+        let (index, is_new) = self.entry::<Image>(path, Source::Image);
+        if is_new {
+            self.pending_images.push(Handle::new(self.id, index));
+        }
+        Handle::new(self.id, index)
+    }
+
+    /// `Failed` for a handle of another manager.
+    pub fn state<T>(&self, handle: Handle<T>) -> &LoadState {
+        self.entry_of(handle)
+            .map_or(&UNKNOWN_HANDLE, |entry| &entry.state)
+    }
+
+    /// The asset - `None` unless it's `Ready`.
+    pub fn get<T: 'static>(&self, handle: Handle<T>) -> Option<&T> {
+        self.entry_of(handle)?
+            .asset
+            .as_ref()
+            .and_then(|asset| asset.downcast_ref())
+    }
+
+    /// The share of the loads that are finished (either ready or failed) in the `[0.0, 1.0]`
+    /// range - `1.0` if nothing was loaded.
+    pub fn progress(&self) -> f32 {
+        if self.entries.is_empty() {
+            return 1.0;
+        }
+
+        let finished = self
+            .entries
+            .iter()
+            .filter(|entry| entry.state != LoadState::Loading)
+            .count();
+        finished as f32 / self.entries.len() as f32
+    }
+
+    /// `true` once every load is either ready or failed.
+    pub fn is_finished(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| entry.state != LoadState::Loading)
+    }
+
+    /// Called by the engine at the start of every frame - hands the new images to the render
+    /// backend and collects the finished loads.
+    pub(crate) fn update(&mut self, images: &mut dyn ImageLoader) {
+        for image in self.pending_images.drain(..) {
+            if let Some(entry) = self.entries.get(image.index) {
+                images.load_image(image, &entry.path);
+            }
+        }
+
+        for (path, result) in self.loader.poll() {
+            let loading = self.entries.iter_mut().filter(|entry| {
+                entry.path == path
+                    && entry.state == LoadState::Loading
+                    && matches!(entry.source, Source::Loader(_))
+            });
+
+            for entry in loading {
+                let Source::Loader(decode) = entry.source else {
+                    continue;
+                };
+
+                match result.clone().and_then(decode) {
+                    Ok(asset) => {
+                        entry.asset = Some(asset);
+                        entry.state = LoadState::Ready;
+                    }
+                    Err(err) => {
+                        log::warn!("Failed to load {path}: {err}");
+                        entry.state = LoadState::Failed(err.to_string());
+                    }
+                }
+            }
+        }
+
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            if entry.state != LoadState::Loading || !matches!(entry.source, Source::Image) {
+                continue;
+            }
+
+            let image = Handle::new(self.id, index);
+            entry.state = images.image_state(image);
+            if let Some(size) = images.image_size(image) {
+                entry.asset = Some(Box::new(Image { size }));
+            }
+        }
+    }
+
+    /// `None` for a handle of another manager.
+    fn entry_of<T>(&self, handle: Handle<T>) -> Option<&Entry> {
+        if handle.manager != self.id {
+            return None;
+        }
+        self.entries.get(handle.index)
+    }
+
+    /// Returns the index of the entry and whether it was just created.
+    fn entry<T: 'static>(&mut self, path: &str, source: Source) -> (usize, bool) {
+        let key = (path.to_owned(), TypeId::of::<T>());
+        if let Some(index) = self.handles.get(&key) {
+            return (*index, false);
+        }
+
+        let index = self.entries.len();
+        self.entries.push(Entry {
+            path: path.to_owned(),
+            source,
+            state: LoadState::Loading,
+            asset: None,
+        });
+        self.handles.insert(key, index);
+        (index, true)
+    }
+
+    /// Whether the bytes of the path are already being loaded for another asset type.
+    fn is_requested(&self, path: &str, except: usize) -> bool {
+        self.entries.iter().enumerate().any(|(index, entry)| {
+            index != except
+                && entry.path == path
+                && entry.state == LoadState::Loading
+                && matches!(entry.source, Source::Loader(_))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RecordingBackend, Vertex2};
+
+    fn assets() -> AssetManager {
+        let loader = MemoryLoader::default()
+            .with_file("greeting.txt", b"hello".to_vec())
+            .with_file("level.json", br#"{ "width": 10 }"#.to_vec())
+            .with_file("broken.json", b"{".to_vec());
+        AssetManager::new(Box::new(loader))
+    }
+
+    #[test]
+    fn load() {
+        let mut assets = assets();
+        let greeting = assets.load::<String>("greeting.txt");
+        assert_eq!(assets.state(greeting), &LoadState::Loading);
+        assert_eq!(assets.get(greeting), None);
+
+        assets.update(&mut RecordingBackend::default());
+        assert_eq!(assets.state(greeting), &LoadState::Ready);
+        assert_eq!(assets.get(greeting).map(String::as_str), Some("hello"));
+    }
+
+    #[test]
+    fn load_json() {
+        let mut assets = assets();
+        let level = assets.load::<serde_json::Value>("level.json");
+        let broken = assets.load::<serde_json::Value>("broken.json");
+        let missing = assets.load::<serde_json::Value>("missing.json");
+        assets.update(&mut RecordingBackend::default());
+
+        assert_eq!(
+            assets.get(level).map(|level| &level["width"]),
+            Some(&10.into())
+        );
+        assert!(matches!(assets.state(broken), LoadState::Failed(_)));
+        assert!(matches!(assets.state(missing), LoadState::Failed(_)));
+    }
+
+    #[test]
+    fn deduplicated_by_path() {
+        let mut assets = assets();
+        let first = assets.load::<String>("greeting.txt");
+        let second = assets.load::<String>("greeting.txt");
+        let bytes = assets.load::<Vec<u8>>("greeting.txt");
+        assert_eq!(first, second);
+
+        assets.update(&mut RecordingBackend::default());
+        assert_eq!(assets.get(bytes), Some(&b"hello".to_vec()));
+        assert_eq!(assets.progress(), 1.0);
+    }
+
+    #[test]
+    fn images() {
+        let mut assets = assets();
+        let mut backend = RecordingBackend::default();
+        backend.set_image_size("player.png", Vertex2::new(16.0, 8.0));
+
+        let player = assets.load_image("player.png");
+        let bomb = assets.load_image("bomb.png");
+        assert_eq!(assets.load_image("player.png"), player);
+        assets.update(&mut backend);
+
+        assert_eq!(
+            assets.get(player),
+            Some(&Image {
+                size: Vertex2::new(16.0, 8.0)
+            })
+        );
+        assert_eq!(assets.state(bomb), &LoadState::Loading);
+    }
+
+    #[test]
+    fn handle_of_another_manager() {
+        let mut other = assets();
+        other.load::<String>("level.json");
+        let greeting = other.load::<String>("greeting.txt");
+
+        let assets = assets();
+        assert!(matches!(assets.state(greeting), LoadState::Failed(_)));
+        assert_eq!(assets.get(greeting), None);
+    }
+
+    #[test]
+    fn handle_of_another_manager_in_range() {
+        let mut other = assets();
+        let level = other.load::<String>("level.json");
+
+        let mut assets = assets();
+        let greeting = assets.load::<String>("greeting.txt");
+        assets.update(&mut RecordingBackend::default());
+        assert_ne!(level, greeting);
+        assert!(matches!(assets.state(level), LoadState::Failed(_)));
+        assert_eq!(assets.get(level), None);
+    }
+
+    #[test]
+    fn progress() {
+        let mut assets = assets();
+        assert_eq!(assets.progress(), 1.0);
+
+        assets.load::<String>("greeting.txt");
+        assets.load_image("player.png");
+        assert_eq!(assets.progress(), 0.0);
+        assert!(!assets.is_finished());
+
+        assets.update(&mut RecordingBackend::default());
+        assert_eq!(assets.progress(), 0.5);
+    }
+}
@@ -0,0 +1,52 @@
+use super::loader::{AssetLoader, LoadResult};
+use crate::error::Result;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Response, js_sys::Uint8Array};
+
+/// Loads assets via the browser's `fetch` - paths are relative to the page (or absolute URLs).
+///
+/// This is the loader of the [Engine](crate::Engine).
+#[derive(Default)]
+pub struct FetchLoader {
+    /// Filled in by the fetch futures as they complete
+    finished: Rc<RefCell<Vec<LoadResult>>>,
+}
+
+impl AssetLoader for FetchLoader {
+    fn start(&mut self, path: &str) {
+        let finished = self.finished.clone();
+        let path = path.to_owned();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = fetch(&path).await;
+            finished.borrow_mut().push((path, result));
+        });
+    }
+
+    fn poll(&mut self) -> Vec<LoadResult> {
+        std::mem::take(&mut *self.finished.borrow_mut())
+    }
+}
+
+async fn fetch(path: &str) -> Result<Vec<u8>> {
+    let window = web_sys::window().ok_or("Failed to find the window")?;
+
+    let response = JsFuture::from(window.fetch_with_str(path))
+        .await
+        .map_err(|_| format!("Failed to fetch {path}"))?
+        .dyn_into::<Response>()
+        .map_err(|_| format!("Failed to cast the response of {path}"))?;
+    if !response.ok() {
+        return Err(format!("Failed to fetch {path}: HTTP {}", response.status()).into());
+    }
+
+    let buffer = response
+        .array_buffer()
+        .map_err(|_| format!("Failed to read the body of {path}"))?;
+    let buffer = JsFuture::from(buffer)
+        .await
+        .map_err(|_| format!("Failed to read the body of {path}"))?;
+
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
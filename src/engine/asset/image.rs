@@ -0,0 +1,30 @@
+//! Images are loaded by the [RenderBackend](crate::RenderBackend) that draws them (e.g. the canvas
+//! keeps an `<img>` element per image) - the [AssetManager](super::AssetManager) only hands out
+//! the handles and tracks their state.
+
+use super::{Handle, LoadState};
+use crate::Vertex2;
+
+/// A loaded image - see [AssetManager::load_image](super::AssetManager::load_image).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Image {
+    /// The size in pixels
+    pub size: Vertex2<f32>,
+}
+
+/// Refers to an image loaded via [AssetManager::load_image](super::AssetManager::load_image) -
+/// it can be stored in [Sprite](crate::Sprite)s right away, even before the image has finished
+/// loading.
+pub type ImageHandle = Handle<Image>;
+
+/// Loads the images of a [RenderBackend](crate::RenderBackend).
+pub trait ImageLoader {
+    /// Starts loading the image - the [AssetManager](super::AssetManager) makes sure that every
+    /// handle is loaded only once.
+    fn load_image(&mut self, image: ImageHandle, path: &str);
+
+    fn image_state(&self, image: ImageHandle) -> LoadState;
+
+    /// The size of the image in pixels - `None` unless the image is ready.
+    fn image_size(&self, image: ImageHandle) -> Option<Vertex2<f32>>;
+}
@@ -0,0 +1,110 @@
+use crate::error::Result;
+use std::{collections::HashMap, path::PathBuf};
+
+/// The result of a finished load - the path along with the loaded bytes.
+pub type LoadResult = (String, Result<Vec<u8>>);
+
+/// Loads the bytes of the assets of the [AssetManager](super::AssetManager) - loads may finish
+/// in any order and at any later time.
+pub trait AssetLoader {
+    fn start(&mut self, path: &str);
+
+    /// Returns the loads that finished since the last call.
+    fn poll(&mut self) -> Vec<LoadResult>;
+}
+
+/// Serves assets from memory - meant for tests.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::MemoryLoader;
+///
+/// let loader = MemoryLoader::default().with_file("level.json", br#"{ "width": 10 }"#.to_vec());
+/// ```
+#[derive(Default, Debug)]
+pub struct MemoryLoader {
+    files: HashMap<String, Vec<u8>>,
+    finished: Vec<LoadResult>,
+}
+
+impl MemoryLoader {
+    pub fn with_file(mut self, path: &str, bytes: Vec<u8>) -> Self {
+        self.files.insert(path.to_owned(), bytes);
+        self
+    }
+}
+
+impl AssetLoader for MemoryLoader {
+    fn start(&mut self, path: &str) {
+        let result = self
+            .files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| format!("File not found: {path}").into());
+        self.finished.push((path.to_owned(), result));
+    }
+
+    fn poll(&mut self) -> Vec<LoadResult> {
+        std::mem::take(&mut self.finished)
+    }
+}
+
+/// Reads assets from the file system (relative to the `root` directory) - it doesn't work in the
+/// browser, it's meant for native tests and tools.
+#[derive(Debug)]
+pub struct FileLoader {
+    root: PathBuf,
+    finished: Vec<LoadResult>,
+}
+
+impl FileLoader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            finished: Vec::default(),
+        }
+    }
+}
+
+impl AssetLoader for FileLoader {
+    fn start(&mut self, path: &str) {
+        let result = std::fs::read(self.root.join(path))
+            .map_err(|err| format!("Failed to read {path}: {err}").into());
+        self.finished.push((path.to_owned(), result));
+    }
+
+    fn poll(&mut self) -> Vec<LoadResult> {
+        std::mem::take(&mut self.finished)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_loader() {
+        let mut loader = MemoryLoader::default().with_file("a.txt", vec![1, 2]);
+        loader.start("a.txt");
+        loader.start("b.txt");
+
+        let finished = loader.poll();
+        assert_eq!(finished[0].0, "a.txt");
+        assert_eq!(finished[0].1.as_ref().ok(), Some(&vec![1, 2]));
+        assert_eq!(finished[1].0, "b.txt");
+        assert!(finished[1].1.is_err());
+        assert!(loader.poll().is_empty());
+    }
+
+    #[test]
+    fn file_loader() {
+        let mut loader = FileLoader::new(env!("CARGO_MANIFEST_DIR"));
+        loader.start("Cargo.toml");
+        loader.start("missing.toml");
+
+        let finished = loader.poll();
+        assert!(finished[0].1.as_ref().is_ok_and(|bytes| !bytes.is_empty()));
+        assert!(finished[1].1.is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use super::component::Layer;
+use crate::{Affine2, Vertex2, dimensions::Dimensions};
+use log::warn;
+use std::collections::HashSet;
+
+/// The largest `smoothing` below `1.0` - the camera would never move towards the target at `1.0`.
+const MAX_SMOOTHING: f32 = 1.0 - f32::EPSILON;
+
+/// Decides which part of the world is visible - the engine applies the camera to every
+/// renderable, except for the ones in the `screen_layers` (e.g. the HUD), which are drawn in
+/// screen pixels.
+///
+/// The default camera shows the world as is (world coordinates are screen pixels), so the
+/// camera can be ignored by applications that don't scroll. Every
+/// [Viewport](super::viewport::Viewport) has its own camera.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{dimensions::Dimensions, prelude::*};
+///
+/// const HUD: Layer = Layer(-1);
+///
+/// fn update_camera(ctx: &mut Context, player: Vertex2<f32>, level: Dimensions) {
+///     let camera = ctx.camera_mut();
+///     camera.screen_layers.insert(HUD);
+///     camera.bounds = Some(level);
+///
+///     // Catch up with the player, with 10% of the distance left after a second
+///     camera.target = Some(player);
+///     camera.smoothing = 0.1;
+///
+///     // Mouse picking in world coordinates
+///     let _cursor = ctx.screen_to_world(ctx.input.mouse.position());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// The world point at the top left corner of the screen - before the zoom and rotation are
+    /// applied (see [center](Self::center) and [look_at](Self::look_at) for working with the
+    /// center instead)
+    pub position: Vertex2<f32>,
+
+    /// Values above `1.0` zoom in, values below `1.0` zoom out - applied around the center of
+    /// the screen
+    pub zoom: f32,
+
+    /// In radians - positive values rotate the view clockwise, around the center of the screen
+    pub rotation: f32,
+
+    /// The world area the view is kept within - the view is centered on the bounds when they're
+    /// smaller than the view
+    pub bounds: Option<Dimensions>,
+
+    /// The world point the camera moves its center to every frame
+    pub target: Option<Vertex2<f32>>,
+
+    /// The share of the distance to the `target` that's left after one second - `0.0` snaps to
+    /// the target right away, values closer to `1.0` follow more slowly (values outside of
+    /// `[0.0, 1.0)` are clamped)
+    pub smoothing: f32,
+
+    /// The layers that are drawn in screen pixels - the camera doesn't move them
+    pub screen_layers: HashSet<Layer>,
+
+    /// The size of the screen (the drawing surface) - kept up to date by the engine
+    screen_size: Vertex2<f32>,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            position: Vertex2::default(),
+            zoom: 1.0,
+            rotation: 0.0,
+            bounds: None,
+            target: None,
+            smoothing: 0.0,
+            screen_layers: HashSet::default(),
+            screen_size: Vertex2::default(),
+        }
+    }
+}
+
+impl Camera {
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Dimensions) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn with_screen_layer(mut self, layer: Layer) -> Self {
+        self.screen_layers.insert(layer);
+        self
+    }
+
+    pub fn screen_size(&self) -> Vertex2<f32> {
+        self.screen_size
+    }
+
+    /// The world point at the center of the screen.
+    pub fn center(&self) -> Vertex2<f32> {
+        self.position + self.half_screen()
+    }
+
+    /// Moves the camera so that the world point is at the center of the screen.
+    pub fn look_at(&mut self, point: Vertex2<f32>) {
+        self.position = point - self.half_screen();
+    }
+
+    /// Whether the renderables of the layer are drawn in screen pixels.
+    pub fn is_screen_layer(&self, layer: Layer) -> bool {
+        self.screen_layers.contains(&layer)
+    }
+
+    /// The matrix that maps world coordinates to screen pixels.
+    pub fn view_matrix(&self) -> Affine2 {
+        let half_screen = self.half_screen();
+        Affine2::from_translation(half_screen)
+            * Affine2::from_rotation(-self.rotation)
+            * Affine2::from_scale(Vertex2::new(self.zoom, self.zoom))
+            * Affine2::from_translation(Vertex2::default() - self.position - half_screen)
+    }
+
+    pub fn world_to_screen(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        self.view_matrix().transform_point(point)
+    }
+
+    pub fn screen_to_world(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        self.view_matrix()
+            .inverse()
+            .map_or(point, |inverse| inverse.transform_point(point))
+    }
+
+    /// The bounding box of the part of the world that's visible on the screen.
+    pub fn visible_area(&self) -> Dimensions {
+        let corners = [
+            Vertex2::new(0.0, 0.0),
+            Vertex2::new(self.screen_size.x, 0.0),
+            Vertex2::new(0.0, self.screen_size.y),
+            self.screen_size,
+        ]
+        .map(|corner| self.screen_to_world(corner));
+
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in corners {
+            min = Vertex2::new(min.x.min(corner.x), min.y.min(corner.y));
+            max = Vertex2::new(max.x.max(corner.x), max.y.max(corner.y));
+        }
+        Dimensions::from_top_left(min, max.x - min.x, max.y - min.y)
+    }
+
+    fn half_screen(&self) -> Vertex2<f32> {
+        Vertex2::new(self.screen_size.x / 2.0, self.screen_size.y / 2.0)
+    }
+
+    pub(crate) fn set_screen_size(&mut self, screen_size: Vertex2<f32>) {
+        self.screen_size = screen_size;
+    }
+
+    /// Called by the engine after the components are updated - follows the target and keeps the
+    /// view within the bounds.
+    pub(crate) fn update(&mut self, delta_time: f32) {
+        if !(0.0..1.0).contains(&self.smoothing) {
+            let smoothing = if self.smoothing.is_nan() {
+                0.0
+            } else {
+                self.smoothing.clamp(0.0, MAX_SMOOTHING)
+            };
+            warn!(
+                "The camera smoothing {} is out of range - {smoothing} is used instead",
+                self.smoothing
+            );
+            self.smoothing = smoothing;
+        }
+
+        if let Some(target) = self.target {
+            // Framerate independent - the same share of the distance is covered every second
+            let progress = match self.smoothing {
+                0.0 => 1.0,
+                smoothing => 1.0 - smoothing.powf(delta_time),
+            };
+            let center = self.center();
+            let distance = target - center;
+            self.look_at(center + Vertex2::new(distance.x * progress, distance.y * progress));
+        }
+
+        if let Some(bounds) = self.bounds {
+            let visible = self.visible_area();
+            self.position.x += clamp_offset(
+                visible.left(),
+                visible.right(),
+                bounds.left(),
+                bounds.right(),
+            );
+            self.position.y += clamp_offset(
+                visible.top(),
+                visible.bottom(),
+                bounds.top(),
+                bounds.bottom(),
+            );
+        }
+    }
+}
+
+/// The offset that moves the `[start, end]` range within the `[min, max]` range - or centers it
+/// if it doesn't fit.
+fn clamp_offset(start: f32, end: f32, min: f32, max: f32) -> f32 {
+    if end - start > max - min {
+        (min + max) / 2.0 - (start + end) / 2.0
+    } else if start < min {
+        min - start
+    } else if end > max {
+        max - end
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_near;
+
+    fn camera() -> Camera {
+        let mut camera = Camera::default();
+        camera.set_screen_size(Vertex2::new(200.0, 100.0));
+        camera
+    }
+
+    #[test]
+    fn default_is_identity() {
+        assert_eq!(camera().view_matrix(), Affine2::IDENTITY);
+    }
+
+    #[test]
+    fn world_to_screen() {
+        let mut camera = camera();
+        camera.position = Vertex2::new(50.0, 0.0);
+        camera.zoom = 2.0;
+
+        // The center of the screen stays in place, everything else moves away from it
+        assert_near(
+            camera.world_to_screen(Vertex2::new(150.0, 50.0)),
+            Vertex2::new(100.0, 50.0),
+        );
+        assert_near(
+            camera.world_to_screen(Vertex2::new(160.0, 50.0)),
+            Vertex2::new(120.0, 50.0),
+        );
+        assert_near(
+            camera.screen_to_world(Vertex2::new(120.0, 50.0)),
+            Vertex2::new(160.0, 50.0),
+        );
+    }
+
+    #[test]
+    fn rotation() {
+        let mut camera = camera();
+        camera.rotation = std::f32::consts::FRAC_PI_2;
+
+        // Rotating the view clockwise makes the world appear to rotate counterclockwise
+        assert_near(
+            camera.world_to_screen(Vertex2::new(110.0, 50.0)),
+            Vertex2::new(100.0, 40.0),
+        );
+        assert_near(
+            camera.screen_to_world(Vertex2::new(100.0, 40.0)),
+            Vertex2::new(110.0, 50.0),
+        );
+    }
+
+    #[test]
+    fn look_at() {
+        let mut camera = camera();
+        camera.look_at(Vertex2::new(0.0, 0.0));
+
+        assert_eq!(camera.position, Vertex2::new(-100.0, -50.0));
+        assert_eq!(camera.center(), Vertex2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn follow() {
+        let mut camera = camera();
+        camera.target = Some(Vertex2::new(200.0, 50.0));
+        camera.smoothing = 0.25;
+
+        camera.update(0.5);
+        assert_near(camera.center(), Vertex2::new(150.0, 50.0));
+
+        camera.smoothing = 0.0;
+        camera.update(0.1);
+        assert_near(camera.center(), Vertex2::new(200.0, 50.0));
+    }
+
+    #[test]
+    fn smoothing_out_of_range() {
+        let mut camera = camera();
+        camera.target = Some(Vertex2::new(200.0, 50.0));
+
+        camera.smoothing = -1.0;
+        camera.update(0.1);
+        assert_eq!(camera.smoothing, 0.0);
+        assert_near(camera.center(), Vertex2::new(200.0, 50.0));
+
+        camera.smoothing = 1.0;
+        camera.update(0.1);
+        assert!(camera.smoothing < 1.0);
+        assert!(camera.center().x.is_finite());
+    }
+
+    #[test]
+    fn bounds() {
+        let mut camera = camera().with_bounds(Dimensions::from_top_left(
+            Vertex2::new(0.0, 0.0),
+            400.0,
+            50.0,
+        ));
+        camera.position = Vertex2::new(300.0, 0.0);
+        camera.update(0.1);
+
+        // Pushed back within the right side, centered vertically since the bounds are too short
+        assert_near(camera.position, Vertex2::new(200.0, -25.0));
+
+        camera.zoom = 2.0;
+        camera.position = Vertex2::new(-100.0, -25.0);
+        camera.update(0.1);
+        assert_near(camera.visible_area().position(), Vertex2::new(50.0, 25.0));
+    }
+
+    #[test]
+    fn screen_layers() {
+        let camera = camera().with_screen_layer(Layer(-1));
+
+        assert!(camera.is_screen_layer(Layer(-1)));
+        assert!(!camera.is_screen_layer(Layer::One));
+    }
+}
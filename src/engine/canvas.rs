@@ -1,39 +1,330 @@
-use crate::{Vertex2, error::Result};
-use wasm_bindgen::JsCast;
-use web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement};
+use super::{
+    asset::{ImageHandle, ImageLoader, LoadState},
+    render_backend::RenderBackend,
+    screen::ScreenLayout,
+};
+use crate::{
+    Affine2, FillRule, Font, LineCap, LineJoin, TextAlign, TextBaseline, TextMeasurer, Vertex2,
+    dimensions::Dimensions, error::Result,
+};
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    CanvasRenderingContext2d, CanvasWindingRule, Element, HtmlCanvasElement, HtmlImageElement,
+    js_sys::Array,
+};
 
 pub struct Canvas {
     element: HtmlCanvasElement,
     context: CanvasRenderingContext2d,
+
+    images: HashMap<ImageHandle, HtmlImageElement>,
+
+    /// An offscreen canvas that tinted images are prepared on
+    tint_element: HtmlCanvasElement,
+    tint_context: CanvasRenderingContext2d,
 }
 
 impl Canvas {
     pub fn new(element: Element) -> Result<Self> {
+        let document = element
+            .owner_document()
+            .ok_or("Could not find the document of the canvas")?;
         let element = element
             .dyn_into::<HtmlCanvasElement>()
             .map_err(|_| "Could not cast element into canvas")?;
+        let context = context_2d(&element)?;
+
+        let tint_element = document
+            .create_element("canvas")
+            .map_err(|_| "Could not create the tint canvas")?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| "Could not cast element into canvas")?;
+        let tint_context = context_2d(&tint_element)?;
+
+        Ok(Self {
+            element,
+            context,
+            images: HashMap::default(),
+            tint_element,
+            tint_context,
+        })
+    }
+
+    /// Covers the window with the canvas while its backing store matches the device pixels.
+    pub fn resize(&self, layout: &ScreenLayout) -> Result<()> {
+        self.element.set_width(layout.canvas_size.x);
+        self.element.set_height(layout.canvas_size.y);
+
+        let style = self.element.style();
+        style
+            .set_property("width", &format!("{}px", layout.window_size.x))
+            .map_err(|_| "Failed to set the width of the canvas")?;
+        style
+            .set_property("height", &format!("{}px", layout.window_size.y))
+            .map_err(|_| "Failed to set the height of the canvas")?;
+        Ok(())
+    }
+}
+
+fn context_2d(element: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d> {
+    let context = element
+        .get_context("2d")
+        .map_err(|_| "Error getting the canvas context")?;
+    let context = context.ok_or("Failed to find a canvas context")?;
+    let context = context
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(|_| "Could not cast canvas into a canvas rendering context")?;
+    Ok(context)
+}
+
+impl ImageLoader for Canvas {
+    fn load_image(&mut self, image: ImageHandle, path: &str) {
+        match HtmlImageElement::new() {
+            Ok(element) => {
+                element.set_src(path);
+                self.images.insert(image, element);
+            }
+            // The image stays in the loading state - it's only possible if the document is gone
+            Err(_) => log::warn!("Failed to create an image element for {path}"),
+        }
+    }
+
+    fn image_state(&self, image: ImageHandle) -> LoadState {
+        match self.images.get(&image) {
+            Some(element) if element.complete() => {
+                // A failed image is complete as well, but it has no size
+                if element.natural_width() == 0 {
+                    LoadState::Failed(format!("Failed to load image {}", element.src()))
+                } else {
+                    LoadState::Ready
+                }
+            }
+            _ => LoadState::Loading,
+        }
+    }
+
+    fn image_size(&self, image: ImageHandle) -> Option<Vertex2<f32>> {
+        let element = self.images.get(&image)?;
+        if !element.complete() || element.natural_width() == 0 {
+            return None;
+        }
+
+        Some(Vertex2::new(
+            element.natural_width() as f32,
+            element.natural_height() as f32,
+        ))
+    }
+}
+
+impl TextMeasurer for Canvas {
+    fn measure_text(&self, text: &str, font: &Font) -> f32 {
+        // The font is set again before any text is drawn, so changing it here is harmless
+        self.context.set_font(&font.css());
+        self.context
+            .measure_text(text)
+            .map(|metrics| metrics.width() as f32)
+            .unwrap_or_default()
+    }
+}
+
+impl RenderBackend for Canvas {
+    fn begin_path(&mut self) {
+        self.context.begin_path();
+    }
+
+    fn move_to(&mut self, point: Vertex2<f32>) {
+        self.context.move_to(point.x.into(), point.y.into());
+    }
+
+    fn line_to(&mut self, point: Vertex2<f32>) {
+        self.context.line_to(point.x.into(), point.y.into());
+    }
+
+    fn quadratic_curve_to(&mut self, control: Vertex2<f32>, point: Vertex2<f32>) {
+        self.context.quadratic_curve_to(
+            control.x.into(),
+            control.y.into(),
+            point.x.into(),
+            point.y.into(),
+        );
+    }
 
-        let context = element
-            .get_context("2d")
-            .map_err(|_| "Error getting the canvas context")?;
-        let context = context.ok_or("Failed to find a canvas context")?;
-        let context = context
-            .dyn_into::<CanvasRenderingContext2d>()
-            .map_err(|_| "Could not cast canvas into a canvas rendering context")?;
+    fn bezier_curve_to(
+        &mut self,
+        control_1: Vertex2<f32>,
+        control_2: Vertex2<f32>,
+        point: Vertex2<f32>,
+    ) {
+        self.context.bezier_curve_to(
+            control_1.x.into(),
+            control_1.y.into(),
+            control_2.x.into(),
+            control_2.y.into(),
+            point.x.into(),
+            point.y.into(),
+        );
+    }
+
+    fn arc_to(&mut self, corner: Vertex2<f32>, point: Vertex2<f32>, radius: f32) {
+        // Only fails for negative radii
+        let _ = self.context.arc_to(
+            corner.x.into(),
+            corner.y.into(),
+            point.x.into(),
+            point.y.into(),
+            radius.into(),
+        );
+    }
+
+    fn close_path(&mut self) {
+        self.context.close_path();
+    }
+
+    fn fill(&mut self, rule: FillRule) {
+        let rule = match rule {
+            FillRule::NonZero => CanvasWindingRule::Nonzero,
+            FillRule::EvenOdd => CanvasWindingRule::Evenodd,
+        };
+        self.context.fill_with_canvas_winding_rule(rule);
+    }
+
+    fn stroke(&mut self) {
+        self.context.stroke();
+    }
+
+    fn set_fill_style(&mut self, style: &str) {
+        self.context.set_fill_style_str(style);
+    }
 
-        Ok(Self { element, context })
+    fn set_stroke_style(&mut self, style: &str) {
+        self.context.set_stroke_style_str(style);
     }
 
-    pub fn context(&self) -> &CanvasRenderingContext2d {
-        &self.context
+    fn set_line_width(&mut self, width: f32) {
+        self.context.set_line_width(width.into());
     }
 
-    pub fn resize(&self, size: Vertex2<u32>) {
-        self.element.set_width(size.x);
-        self.element.set_height(size.y);
+    fn set_line_join(&mut self, join: LineJoin) {
+        self.context.set_line_join(match join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        });
     }
 
-    pub fn clear(&self) {
+    fn set_line_cap(&mut self, cap: LineCap) {
+        self.context.set_line_cap(match cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        });
+    }
+
+    fn set_line_dash(&mut self, segments: &[f32]) {
+        let segments: Array = segments
+            .iter()
+            .map(|segment| JsValue::from_f64((*segment).into()))
+            .collect();
+        // Only fails for negative or non-finite values, which the canvas ignores anyway
+        let _ = self.context.set_line_dash(&segments);
+    }
+
+    fn set_global_alpha(&mut self, alpha: f32) {
+        self.context.set_global_alpha(alpha.into());
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.context.set_font(font);
+    }
+
+    fn set_text_align(&mut self, align: TextAlign) {
+        self.context.set_text_align(match align {
+            TextAlign::Left => "left",
+            TextAlign::Center => "center",
+            TextAlign::Right => "right",
+        });
+    }
+
+    fn set_text_baseline(&mut self, baseline: TextBaseline) {
+        self.context.set_text_baseline(match baseline {
+            TextBaseline::Top => "top",
+            TextBaseline::Middle => "middle",
+            TextBaseline::Bottom => "bottom",
+            TextBaseline::Alphabetic => "alphabetic",
+        });
+    }
+
+    fn fill_text(&mut self, text: &str, position: Vertex2<f32>, max_width: Option<f32>) {
+        let (x, y) = (position.x.into(), position.y.into());
+        // Only fails for non-finite values, which the canvas ignores anyway
+        let _ = match max_width {
+            Some(max_width) => self
+                .context
+                .fill_text_with_max_width(text, x, y, max_width.into()),
+            None => self.context.fill_text(text, x, y),
+        };
+    }
+
+    fn stroke_text(&mut self, text: &str, position: Vertex2<f32>, max_width: Option<f32>) {
+        let (x, y) = (position.x.into(), position.y.into());
+        let _ = match max_width {
+            Some(max_width) => {
+                self.context
+                    .stroke_text_with_max_width(text, x, y, max_width.into())
+            }
+            None => self.context.stroke_text(text, x, y),
+        };
+    }
+
+    fn translate(&mut self, offset: Vertex2<f32>) {
+        // Only fails for non-finite values, which the canvas ignores anyway
+        let _ = self.context.translate(offset.x.into(), offset.y.into());
+    }
+
+    fn rotate(&mut self, radians: f32) {
+        let _ = self.context.rotate(radians.into());
+    }
+
+    fn scale(&mut self, scale: Vertex2<f32>) {
+        let _ = self.context.scale(scale.x.into(), scale.y.into());
+    }
+
+    fn set_transform(&mut self, matrix: &Affine2) {
+        let _ = self.context.set_transform(
+            matrix.a.into(),
+            matrix.b.into(),
+            matrix.c.into(),
+            matrix.d.into(),
+            matrix.e.into(),
+            matrix.f.into(),
+        );
+    }
+
+    fn reset_transform(&mut self) {
+        let _ = self.context.reset_transform();
+    }
+
+    fn save(&mut self) {
+        self.context.save();
+    }
+
+    fn restore(&mut self) {
+        self.context.restore();
+    }
+
+    fn clip(&mut self, area: &Dimensions) {
+        self.context.begin_path();
+        self.context.rect(
+            area.left() as f64,
+            area.top() as f64,
+            area.width() as f64,
+            area.height() as f64,
+        );
+        self.context.clip();
+    }
+
+    fn clear(&mut self) {
         self.context.clear_rect(
             0.0,
             0.0,
@@ -41,4 +332,76 @@ impl Canvas {
             self.element.height() as f64,
         );
     }
+
+    fn size(&self) -> Vertex2<f32> {
+        Vertex2::new(self.element.width() as f32, self.element.height() as f32)
+    }
+
+    fn draw_image(
+        &mut self,
+        image: ImageHandle,
+        source: &Dimensions,
+        destination: &Dimensions,
+        tint: Option<&str>,
+    ) {
+        let Some(element) = self.images.get(&image) else {
+            return;
+        };
+
+        let (sx, sy) = (source.left().into(), source.top().into());
+        let (sw, sh) = (source.width().into(), source.height().into());
+        let (dx, dy) = (destination.left().into(), destination.top().into());
+        let (dw, dh) = (destination.width().into(), destination.height().into());
+
+        let Some(tint) = tint else {
+            // Only fails for images that are not loaded yet, which the canvas can't draw anyway
+            let _ = self
+                .context
+                .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    element, sx, sy, sw, sh, dx, dy, dw, dh,
+                );
+            return;
+        };
+
+        // Multiply the image with the tint color, then cut the result to the shape of the image
+        // so that the transparent pixels stay transparent
+        self.tint_element.set_width(source.width().ceil() as u32);
+        self.tint_element.set_height(source.height().ceil() as u32);
+        let copy_image = |context: &CanvasRenderingContext2d| {
+            let _ = context
+                .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    element, sx, sy, sw, sh, 0.0, 0.0, sw, sh,
+                );
+        };
+
+        copy_image(&self.tint_context);
+        let _ = self.tint_context.set_global_composite_operation("multiply");
+        self.tint_context.set_fill_style_str(tint);
+        self.tint_context.fill_rect(0.0, 0.0, sw, sh);
+        let _ = self
+            .tint_context
+            .set_global_composite_operation("destination-in");
+        copy_image(&self.tint_context);
+        let _ = self
+            .tint_context
+            .set_global_composite_operation("source-over");
+
+        let _ = self
+            .context
+            .draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                &self.tint_element,
+                0.0,
+                0.0,
+                sw,
+                sh,
+                dx,
+                dy,
+                dw,
+                dh,
+            );
+    }
+
+    fn canvas_context(&self) -> Option<&CanvasRenderingContext2d> {
+        Some(&self.context)
+    }
 }
@@ -1,27 +1,97 @@
+pub mod sprite;
+pub mod style;
+pub mod text;
 pub mod transform;
 
-use super::Context;
-use crate::Vertex2;
+use super::{Context, viewport::ViewportMask};
+use crate::{Path, Vertex2, error::Result, tilemap::TileLayer};
+use sprite::Sprite;
+use std::rc::Rc;
+use style::Style;
+use text::Text;
 use transform::Transform;
-use web_sys::CanvasRenderingContext2d;
 
 /// Defines the priority of the [Renderable](Renderable) - lower layers have higher priority (e.g.
-/// a `Layer::One` [Renderable](Renderable) will always appear on top of a `Layer::Two`
-/// [Renderable](Renderable)
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub enum Layer {
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
+/// a `Layer(1)` [Renderable](Renderable) will always appear on top of a `Layer(2)`
+/// [Renderable](Renderable)). Any `i32` can be used, so there's always room for one more layer in
+/// between or in front (e.g. `Layer(-100)` for debug overlays).
+///
+/// The `Layer::One` to `Layer::Five` constants are `Layer(1)` to `Layer(5)`.
+///
+/// Renderables within the same layer are drawn in the order the component tree is traversed
+/// (a renderable drawn later appears on top):
+/// - the renderables of a component are drawn in the order of its `renderables` field
+/// - sibling components are drawn in the order they are returned by `children`
+/// - all siblings are drawn before any of their children, so children are drawn on top of both
+///   their parent and their parent's siblings
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Layer(pub i32);
+
+#[allow(non_upper_case_globals)]
+impl Layer {
+    pub const One: Layer = Layer(1);
+    pub const Two: Layer = Layer(2);
+    pub const Three: Layer = Layer(3);
+    pub const Four: Layer = Layer(4);
+    pub const Five: Layer = Layer(5);
+}
+
+/// What a [Renderable](Renderable) displays - use `into()` to create it from a [Path](Path), a
+/// list of vertices (a single closed polygon), a [Text](Text) or a [Sprite](Sprite).
+///
+/// Tiles are managed by the [Tilemap](crate::tilemap::Tilemap) component. The layer is shared, so
+/// cloning a shape doesn't copy its grid - and two tile shapes are only equal when they share the
+/// same layer.
+#[derive(Clone, Debug)]
+pub enum Shape {
+    Path(Path),
+    Text(Text),
+    Sprite(Sprite),
+    Tiles(Rc<TileLayer>),
+}
+
+impl PartialEq for Shape {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Shape::Path(a), Shape::Path(b)) => a == b,
+            (Shape::Text(a), Shape::Text(b)) => a == b,
+            (Shape::Sprite(a), Shape::Sprite(b)) => a == b,
+            (Shape::Tiles(a), Shape::Tiles(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl From<Path> for Shape {
+    fn from(path: Path) -> Self {
+        Shape::Path(path)
+    }
+}
+
+impl From<Vec<Vertex2<f32>>> for Shape {
+    fn from(vertices: Vec<Vertex2<f32>>) -> Self {
+        Shape::Path(vertices.into())
+    }
+}
+
+impl From<Text> for Shape {
+    fn from(text: Text) -> Self {
+        Shape::Text(text)
+    }
+}
+
+impl From<Sprite> for Shape {
+    fn from(sprite: Sprite) -> Self {
+        Shape::Sprite(sprite)
+    }
 }
 
 /// The `Renderable` is a description of something that can be rendered.
 /// Multiple renderables can be added to a single [Component](Component)
 ///
 /// Make sure to use the `shape_factory` where possible to avoid specifying lists of vertices
-/// manually.
+/// manually - see [Path](Path) for open lines and shapes with holes, [Text](Text) for
+/// displaying text and [Sprite](Sprite) for displaying images.
 ///
 /// ## Example
 ///
@@ -29,34 +99,59 @@ pub enum Layer {
 /// use twors::{prelude::*, shape_factory};
 ///
 /// pub fn new() -> Renderable {
-///     Renderable {
-///         transform: Transform::default(),
-///         vertices: shape_factory::square(40.0),
-///         style: |ctx: &CanvasRenderingContext2d| {
-///             ctx.set_fill_style_str("orange");
-///             ctx.set_line_width(1.0);
-///             ctx.set_stroke_style_str("black");
-///             ctx.stroke();
-///             ctx.fill();
-///         },
-///         layer: twors::Layer::One,
-///     }
+///     Renderable::new(shape_factory::square(40.0), Layer::One)
+///         .with_style(Style::filled("orange").with_stroke("black"))
 /// }
 /// ```
 #[derive(Debug)]
 pub struct Renderable {
     pub transform: Transform,
 
-    /// The engine will automatically create a shape from the vertices by using the
-    /// canvas' `move_to` and `line_to` methods until the shape is complete. It will
-    /// **THEN** call the `style` callback.
-    pub vertices: Vec<Vertex2<f32>>,
+    /// The engine will automatically create a shape from a path by using the canvas' path
+    /// methods (`move_to`, `line_to`, the curve methods) until the shape is complete. It will
+    /// **THEN** fill and stroke it according to the `style` - text is filled and stroked the same
+    /// way.
+    ///
+    /// Use `into()` to create a path of a single closed polygon from a list of vertices.
+    pub shape: Shape,
 
-    /// This callback is passed bindings for the canvas API - use it to style the `vertices`.
-    pub style: fn(&CanvasRenderingContext2d),
+    pub style: Style,
 
     /// Rendering priority.
     pub layer: Layer,
+
+    /// The [Viewport](super::viewport::Viewport)s the renderable is drawn in - use
+    /// `ViewportMask::ALL` unless the renderable belongs to a single player's view (e.g. their
+    /// HUD).
+    pub viewports: ViewportMask,
+}
+
+impl Renderable {
+    /// A renderable with the default transform and style that's drawn in every viewport.
+    pub fn new(shape: impl Into<Shape>, layer: Layer) -> Self {
+        Self {
+            transform: Transform::default(),
+            shape: shape.into(),
+            style: Style::default(),
+            layer,
+            viewports: ViewportMask::ALL,
+        }
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_viewports(mut self, viewports: ViewportMask) -> Self {
+        self.viewports = viewports;
+        self
+    }
 }
 
 #[doc(hidden)]
@@ -118,7 +213,33 @@ pub trait ComponentLifecycle {
     /// - use the `&mut Context` value to
     ///     - read the delta time for multiplication of values used for movement over time
     ///     - read input from the mouse/keyboard
+    ///
     fn update(&mut self, ctx: &mut Context);
+
+    /// The fallible version of `update` that the engine calls - override it instead of `update`
+    /// when the update can fail (`update` then has an empty body).
+    ///
+    /// The errors are handled by the engine's [ErrorPolicy](super::error_policy::ErrorPolicy) -
+    /// the other components are still updated and the frame is still rendered.
+    fn try_update(&mut self, ctx: &mut Context) -> Result<()> {
+        self.update(ctx);
+        Ok(())
+    }
+
+    /// Runs at the constant rate of the engine's [FixedTimestep](super::time::FixedTimestep)
+    /// (zero or more times per frame, before `update`) - this is where movement and collisions
+    /// that have to behave the same on every framerate go. It's never called unless the engine
+    /// is set up with a fixed timestep.
+    ///
+    /// Keep reading input in `update` - a frame might not run any fixed steps, so one-frame
+    /// states like `is_pressed` could be missed.
+    fn fixed_update(&mut self, _ctx: &mut Context) {}
+
+    /// The fallible version of `fixed_update` - see [try_update](Self::try_update).
+    fn try_fixed_update(&mut self, ctx: &mut Context) -> Result<()> {
+        self.fixed_update(ctx);
+        Ok(())
+    }
 }
 
 pub trait Component: ComponentLifecycle + ComponentGetter {}
@@ -0,0 +1,129 @@
+use crate::{ImageHandle, Vertex2, dimensions::Dimensions};
+
+/// An image (or a part of one) that a [Renderable](super::Renderable) can display.
+///
+/// The sprite is centered on the position of the renderable (the same way as the shapes of the
+/// `shape_factory`) and it's moved, scaled and rotated with its [Transform](super::Transform).
+/// Only the `alpha` of the renderable's [Style](super::Style) applies to sprites.
+///
+/// Nothing is drawn until the image has finished loading.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Sprite, Vertex2, dimensions::Dimensions, prelude::*};
+///
+/// fn player_sprite(ctx: &mut Context) -> Sprite {
+///     let sheet = ctx.assets.load_image("assets/player.png");
+///
+///     // The second 16x16 frame of the sheet, facing left and drawn 4 times as big
+///     Sprite::new(sheet)
+///         .with_source(Dimensions::from_top_left(Vertex2::new(16.0, 0.0), 16.0, 16.0))
+///         .with_size(Vertex2::new(64.0, 64.0))
+///         .with_flip_x(true)
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sprite {
+    pub image: ImageHandle,
+
+    /// The part of the image to draw in image pixels - the whole image if this is `None`
+    pub source: Option<Dimensions>,
+
+    /// The size of the sprite before the transform is applied - the size of the `source` if this
+    /// is `None`
+    pub size: Option<Vertex2<f32>>,
+
+    /// Mirrors the sprite horizontally
+    pub flip_x: bool,
+
+    /// Mirrors the sprite vertically
+    pub flip_y: bool,
+
+    /// Any CSS color that the sprite is multiplied with (e.g. `"red"` to make it look hurt)
+    pub tint: Option<String>,
+}
+
+impl Sprite {
+    pub fn new(image: ImageHandle) -> Self {
+        Self {
+            image,
+            source: None,
+            size: None,
+            flip_x: false,
+            flip_y: false,
+            tint: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: Dimensions) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_size(mut self, size: Vertex2<f32>) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_tint(mut self, tint: &str) -> Self {
+        self.tint = Some(tint.to_owned());
+        self
+    }
+
+    /// The part of the image to draw and the local rectangle to draw it into (centered on the
+    /// origin).
+    pub(crate) fn layout(&self, image_size: Vertex2<f32>) -> (Dimensions, Dimensions) {
+        let source = self.source.unwrap_or(Dimensions::from_top_left(
+            Vertex2::default(),
+            image_size.x,
+            image_size.y,
+        ));
+        let size = self
+            .size
+            .unwrap_or(Vertex2::new(source.width(), source.height()));
+
+        (source, Dimensions::new(Vertex2::default(), size.x, size.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_of_whole_image() {
+        let sprite = Sprite::new(ImageHandle::new(0, 0));
+
+        assert_eq!(
+            sprite.layout(Vertex2::new(20.0, 10.0)),
+            (
+                Dimensions::from_top_left(Vertex2::new(0.0, 0.0), 20.0, 10.0),
+                Dimensions::new(Vertex2::new(0.0, 0.0), 20.0, 10.0),
+            )
+        );
+    }
+
+    #[test]
+    fn layout_of_source() {
+        let source = Dimensions::from_top_left(Vertex2::new(16.0, 0.0), 16.0, 8.0);
+        let sprite = Sprite::new(ImageHandle::new(0, 0))
+            .with_source(source)
+            .with_size(Vertex2::new(32.0, 16.0));
+
+        assert_eq!(
+            sprite.layout(Vertex2::new(64.0, 64.0)),
+            (source, Dimensions::new(Vertex2::new(0.0, 0.0), 32.0, 16.0))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::engine::render_backend::RenderBackend;
+use std::fmt::{self, Debug, Formatter};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+/// Decides which areas of a self-intersecting path (or a path with holes) are inside of the
+/// shape - see <https://developer.mozilla.org/en-US/docs/Web/API/CanvasRenderingContext2D/fill#fillrule>
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+/// A closure for canvas calls that a [Style](Style) can't describe - see [Style::custom].
+pub type CustomStyle = Box<dyn Fn(&mut dyn RenderBackend)>;
+
+/// Describes how the engine fills and strokes the shape of a [Renderable](super::Renderable).
+///
+/// Colors are any valid CSS color (e.g. `"orange"`, `"#FFBF00"` or `"rgb(255 0 0 / 50%)"`). The
+/// fields can be modified at any time (e.g. in `update`) to change the look of a renderable.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::Style;
+///
+/// let style = Style::filled("orange").with_stroke("black");
+/// ```
+pub struct Style {
+    /// The shape is not filled if this is `None`
+    pub fill: Option<String>,
+
+    /// The shape is not stroked if this is `None`
+    pub stroke: Option<String>,
+
+    pub line_width: f32,
+    pub line_join: LineJoin,
+    pub line_cap: LineCap,
+
+    /// Alternating lengths of lines and gaps - an empty list draws a solid line
+    pub line_dash: Vec<f32>,
+
+    /// The opacity in the `[0.0, 1.0]` range
+    pub alpha: f32,
+    pub fill_rule: FillRule,
+
+    /// Called after the shape is filled and stroked (while the path is still active) - use it as
+    /// an escape hatch for custom drawing (e.g. via [RenderBackend::canvas_context]).
+    pub custom: Option<CustomStyle>,
+}
+
+impl Style {
+    pub fn filled(color: impl Into<String>) -> Self {
+        Style::default().with_fill(color)
+    }
+
+    pub fn stroked(color: impl Into<String>) -> Self {
+        Style::default().with_stroke(color)
+    }
+
+    pub fn with_fill(mut self, color: impl Into<String>) -> Self {
+        self.fill = Some(color.into());
+        self
+    }
+
+    pub fn with_stroke(mut self, color: impl Into<String>) -> Self {
+        self.stroke = Some(color.into());
+        self
+    }
+
+    pub fn with_line_width(mut self, line_width: f32) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    pub fn with_line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    pub fn with_line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    pub fn with_line_dash(mut self, line_dash: Vec<f32>) -> Self {
+        self.line_dash = line_dash;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    pub fn with_custom(mut self, custom: impl Fn(&mut dyn RenderBackend) + 'static) -> Self {
+        self.custom = Some(Box::new(custom));
+        self
+    }
+}
+
+impl Default for Style {
+    /// Neither filled nor stroked - a `1.0` wide solid line and full opacity.
+    fn default() -> Self {
+        Self {
+            fill: None,
+            stroke: None,
+            line_width: 1.0,
+            line_join: LineJoin::default(),
+            line_cap: LineCap::default(),
+            line_dash: Vec::default(),
+            alpha: 1.0,
+            fill_rule: FillRule::default(),
+            custom: None,
+        }
+    }
+}
+
+impl Debug for Style {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Style")
+            .field("fill", &self.fill)
+            .field("stroke", &self.stroke)
+            .field("line_width", &self.line_width)
+            .field("line_join", &self.line_join)
+            .field("line_cap", &self.line_cap)
+            .field("line_dash", &self.line_dash)
+            .field("alpha", &self.alpha)
+            .field("fill_rule", &self.fill_rule)
+            .field("custom", &self.custom.as_ref().map(|_| "Fn"))
+            .finish()
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{Vertex2, dimensions::Dimensions};
+
+/// The horizontal position of the text relative to its origin.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum TextAlign {
+    /// The text starts at the origin
+    #[default]
+    Left,
+    Center,
+    /// The text ends at the origin
+    Right,
+}
+
+/// The vertical position of the text relative to its origin.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum TextBaseline {
+    /// The top of the first line is at the origin - further lines go down
+    Top,
+    /// The block of lines is vertically centered on the origin
+    Middle,
+    /// The bottom of the last line is at the origin - previous lines go up
+    Bottom,
+    /// The first line sits on the origin the way handwriting sits on a ruled line - further lines
+    /// go down
+    #[default]
+    Alphabetic,
+}
+
+/// A font weight from `100` (thin) to `900` (black) - see
+/// <https://developer.mozilla.org/en-US/docs/Web/CSS/font-weight#common_weight_name_mapping>
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct FontWeight(pub u16);
+
+#[allow(non_upper_case_globals)]
+impl FontWeight {
+    pub const Normal: FontWeight = FontWeight(400);
+    pub const Bold: FontWeight = FontWeight(700);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        FontWeight::Normal
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Font {
+    /// Any CSS font family (e.g. `"monospace"` or `"'Press Start 2P', monospace"`)
+    pub family: String,
+
+    /// The size in pixels
+    pub size: f32,
+
+    pub weight: FontWeight,
+}
+
+impl Font {
+    pub fn new(family: &str, size: f32) -> Self {
+        Self {
+            family: family.to_owned(),
+            size,
+            weight: FontWeight::default(),
+        }
+    }
+
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// The font in the CSS shorthand format that the canvas expects (e.g. `"700 16px monospace"`).
+    pub fn css(&self) -> String {
+        format!("{} {}px {}", self.weight.0, self.size, self.family)
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Font::new("sans-serif", 16.0)
+    }
+}
+
+/// Measures the width of a single line of text - the canvas measures the real glyphs, while the
+/// [RecordingBackend](crate::RecordingBackend) uses a fixed width per character so that tests
+/// are deterministic.
+pub trait TextMeasurer {
+    fn measure_text(&self, text: &str, font: &Font) -> f32;
+}
+
+/// A (possibly multi-line) text that a [Renderable](super::Renderable) can display.
+///
+/// The color comes from the `style` of the renderable - the text is filled with the `fill` color
+/// and outlined with the `stroke` color. Lines are broken at `\n` and - when `max_width` is set -
+/// wrapped at spaces so that no line is wider than `max_width`.
+///
+/// The fields can be modified at any time (e.g. to update a score counter in `update`).
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Font, FontWeight, Text, TextAlign};
+///
+/// let score = Text::new("Score: 0")
+///     .with_font(Font::new("monospace", 24.0).with_weight(FontWeight::Bold))
+///     .with_align(TextAlign::Center)
+///     .with_max_width(200.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Text {
+    pub content: String,
+    pub font: Font,
+    pub align: TextAlign,
+    pub baseline: TextBaseline,
+
+    /// Lines longer than this are wrapped - the text is never wrapped if this is `None`
+    pub max_width: Option<f32>,
+
+    /// The distance between the lines as a multiple of the font size
+    pub line_height: f32,
+}
+
+impl Text {
+    pub fn new(content: &str) -> Self {
+        Self {
+            content: content.to_owned(),
+            font: Font::default(),
+            align: TextAlign::default(),
+            baseline: TextBaseline::default(),
+            max_width: None,
+            line_height: 1.2,
+        }
+    }
+
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_baseline(mut self, baseline: TextBaseline) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    /// The lines that the text is displayed as - after breaking at `\n` and wrapping to the
+    /// `max_width`. A single word that is wider than `max_width` is kept on its own line.
+    pub fn lines(&self, measurer: &dyn TextMeasurer) -> Vec<String> {
+        let mut lines = Vec::default();
+        for paragraph in self.content.split('\n') {
+            let Some(max_width) = self.max_width else {
+                lines.push(paragraph.to_owned());
+                continue;
+            };
+
+            let mut line = String::default();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() {
+                    word.to_owned()
+                } else {
+                    format!("{line} {word}")
+                };
+
+                if !line.is_empty() && measurer.measure_text(&candidate, &self.font) > max_width {
+                    lines.push(std::mem::replace(&mut line, word.to_owned()));
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// The `y` offset of every line (relative to the origin) that the canvas should draw it at.
+    pub(crate) fn line_offsets(&self, line_count: usize) -> impl Iterator<Item = f32> {
+        let line_height = self.line_height_px();
+        let block_height = line_height * line_count.saturating_sub(1) as f32;
+        let first = match self.baseline {
+            TextBaseline::Top | TextBaseline::Alphabetic => 0.0,
+            TextBaseline::Middle => -block_height / 2.0,
+            TextBaseline::Bottom => -block_height,
+        };
+        (0..line_count).map(move |line| first + line as f32 * line_height)
+    }
+
+    /// The bounding box of the text in the local space of the renderable (the origin is the
+    /// position of the renderable) - use it for layout and hit-testing.
+    ///
+    /// > **Note:** the height is based on the font size - the canvas doesn't report the exact
+    /// > height of the glyphs, so accents and descenders may stick out slightly.
+    pub fn measure(&self, measurer: &dyn TextMeasurer) -> Dimensions {
+        let lines = self.lines(measurer);
+        let width = lines
+            .iter()
+            .map(|line| measurer.measure_text(line, &self.font))
+            .fold(0.0, f32::max);
+        let size = self.font.size;
+        let height = self.line_height_px() * (lines.len() - 1) as f32 + size;
+
+        let left = match self.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => -width / 2.0,
+            TextAlign::Right => -width,
+        };
+        let first_line = self.line_offsets(lines.len()).next().unwrap_or_default();
+        let top = first_line
+            + match self.baseline {
+                TextBaseline::Top => 0.0,
+                TextBaseline::Middle => -size / 2.0,
+                TextBaseline::Bottom => -size,
+                // Roughly the height of the capital letters
+                TextBaseline::Alphabetic => -size * 0.8,
+            };
+
+        Dimensions::new(
+            Vertex2::new(left + width / 2.0, top + height / 2.0),
+            width,
+            height,
+        )
+    }
+
+    fn line_height_px(&self) -> f32 {
+        self.font.size * self.line_height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every character is `1` pixel wide
+    struct CharCounter;
+
+    impl TextMeasurer for CharCounter {
+        fn measure_text(&self, text: &str, _: &Font) -> f32 {
+            text.chars().count() as f32
+        }
+    }
+
+    #[test]
+    fn css() {
+        let font = Font::new("monospace", 12.5).with_weight(FontWeight::Bold);
+        assert_eq!(font.css(), "700 12.5px monospace");
+    }
+
+    #[test]
+    fn lines_are_broken_at_newlines() {
+        let text = Text::new("first\nsecond line");
+        assert_eq!(text.lines(&CharCounter), ["first", "second line"]);
+    }
+
+    #[test]
+    fn lines_are_wrapped() {
+        let text = Text::new("a bb ccc dddddddd e\nff g").with_max_width(6.0);
+        assert_eq!(
+            text.lines(&CharCounter),
+            ["a bb", "ccc", "dddddddd", "e", "ff g"]
+        );
+    }
+
+    #[test]
+    fn line_offsets() {
+        let text = Text::new("")
+            .with_font(Font::new("serif", 10.0))
+            .with_line_height(2.0);
+
+        let offsets: Vec<f32> = text.line_offsets(3).collect();
+        assert_eq!(offsets, [0.0, 20.0, 40.0]);
+
+        let text = text.with_baseline(TextBaseline::Middle);
+        let offsets: Vec<f32> = text.line_offsets(3).collect();
+        assert_eq!(offsets, [-20.0, 0.0, 20.0]);
+
+        let text = text.with_baseline(TextBaseline::Bottom);
+        let offsets: Vec<f32> = text.line_offsets(3).collect();
+        assert_eq!(offsets, [-40.0, -20.0, 0.0]);
+    }
+
+    #[test]
+    fn measure() {
+        let text = Text::new("abcd\nab")
+            .with_font(Font::new("serif", 10.0))
+            .with_line_height(2.0)
+            .with_align(TextAlign::Center)
+            .with_baseline(TextBaseline::Top);
+
+        let dimensions = text.measure(&CharCounter);
+        assert_eq!(dimensions.left(), -2.0);
+        assert_eq!(dimensions.right(), 2.0);
+        assert_eq!(dimensions.top(), 0.0);
+        assert_eq!(dimensions.bottom(), 30.0);
+    }
+
+    #[test]
+    fn measure_right_aligned_bottom() {
+        let text = Text::new("abcd")
+            .with_font(Font::new("serif", 10.0))
+            .with_align(TextAlign::Right)
+            .with_baseline(TextBaseline::Bottom);
+
+        let dimensions = text.measure(&CharCounter);
+        assert_eq!(dimensions.left(), -4.0);
+        assert_eq!(dimensions.right(), 0.0);
+        assert_eq!(dimensions.top(), -10.0);
+        assert_eq!(dimensions.bottom(), 0.0);
+    }
+}
@@ -1,64 +1,177 @@
-use crate::Vertex2;
-use std::ops::{Add, Sub};
+use crate::{Affine2, Vertex2};
+use log::warn;
+use std::{
+    cell::Cell,
+    ops::{Add, Mul, Sub},
+};
 
 const DEFAULT_SCALE: Vertex2<f32> = const { Vertex2::new(1.0, 1.0) };
 
-/// Directly modify the `position` and `scale` fields - the engine will automatically pick up the
-/// changes and move your `Renderable`s
+/// Directly modify the `position`, `scale` and `rotation` fields - the engine will automatically
+/// pick up the changes and move your `Renderable`s
+///
+/// Children are positioned relative to their parent - moving, scaling or rotating a parent will
+/// move, scale or rotate (orbit) its children as well.
+///
+/// The absolute (world) matrix is cached and only recalculated when the local fields or the
+/// parent change, so [absolute](Self::absolute) and [absolute_matrix](Self::absolute_matrix) are
+/// cheap to call repeatedly.
 #[derive(Clone, Debug)]
 pub struct Transform {
     pub position: Vertex2<f32>,
     pub scale: Vertex2<f32>,
-    pub parent: Option<Box<Transform>>,
+
+    /// The rotation in radians - positive values rotate clockwise since the canvas' `y` axis
+    /// points down.
+    pub rotation: f32,
+
+    /// The local point that the `scale` and `rotation` are applied around - `(0, 0)` (the center
+    /// of the shapes created via the `shape_factory`) by default.
+    pub pivot: Vertex2<f32>,
+
+    /// Kept up to date by the engine - use [absolute](Self::absolute) to read it.
+    pub(crate) hierarchy: Hierarchy,
+}
+
+/// The absolute matrix of the parent component along with the cached absolute matrix of the
+/// transform itself.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Hierarchy {
+    parent: Affine2,
+    cache: Cell<Option<AbsoluteCache>>,
+}
+
+/// The absolute matrix along with the local fields it was calculated from - the cache is stale as
+/// soon as any of them changes.
+#[derive(Copy, Clone, Debug)]
+struct AbsoluteCache {
+    position: Vertex2<f32>,
+    scale: Vertex2<f32>,
+    rotation: f32,
+    pivot: Vertex2<f32>,
+    matrix: Affine2,
 }
 
 impl Transform {
     pub fn from_position(position: Vertex2<f32>) -> Self {
         Self {
             position,
-            scale: DEFAULT_SCALE,
-            parent: None,
+            ..Transform::default()
         }
     }
 
-    pub fn absolute(&self) -> Transform {
-        let parent = self.get_parent();
-        parent + self
+    pub fn with_scale(mut self, scale: Vertex2<f32>) -> Self {
+        self.scale = scale;
+        self
     }
 
-    pub fn set_absolute(&mut self, transform: &Transform) {
-        let parent = self.get_parent();
-        *self = transform.clone() - &parent;
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_pivot(mut self, pivot: Vertex2<f32>) -> Self {
+        self.pivot = pivot;
+        self
     }
 
-    fn get_parent(&self) -> Transform {
-        match &self.parent {
-            Some(parent) => *parent.clone(),
-            None => Transform::default(),
+    /// The matrix that maps points from the local space of the transform to the space of its
+    /// parent.
+    pub fn matrix(&self) -> Affine2 {
+        Affine2::from(self)
+    }
+
+    /// The matrix that maps points from the local space of the transform to the canvas.
+    pub fn absolute_matrix(&self) -> Affine2 {
+        if let Some(cache) = self.hierarchy.cache.get()
+            && cache.position == self.position
+            && cache.scale == self.scale
+            && cache.rotation == self.rotation
+            && cache.pivot == self.pivot
+        {
+            return cache.matrix;
+        }
+
+        let matrix = self.hierarchy.parent * self.matrix();
+        self.hierarchy.cache.set(Some(AbsoluteCache {
+            position: self.position,
+            scale: self.scale,
+            rotation: self.rotation,
+            pivot: self.pivot,
+            matrix,
+        }));
+        matrix
+    }
+
+    /// The absolute matrix of the parent component (the identity matrix for root components).
+    pub fn parent_matrix(&self) -> Affine2 {
+        self.hierarchy.parent
+    }
+
+    pub(crate) fn set_parent_matrix(&mut self, parent: Affine2) {
+        if self.hierarchy.parent != parent {
+            self.hierarchy.parent = parent;
+            self.hierarchy.cache.set(None);
         }
     }
+
+    pub fn absolute(&self) -> Transform {
+        Transform::from(self.absolute_matrix())
+    }
+
+    /// Replaces the transform with the given absolute transform, relative to the current parent.
+    pub fn set_absolute(&mut self, transform: &Transform) {
+        let Some(parent_inverse) = self.parent_matrix().inverse() else {
+            warn!("Can't set the absolute transform of a child of a zero-scaled parent");
+            return;
+        };
+
+        let pivot = self.pivot;
+        *self = Transform {
+            pivot,
+            hierarchy: Hierarchy {
+                parent: self.hierarchy.parent,
+                cache: Cell::new(None),
+            },
+            ..Transform::from(parent_inverse * transform.matrix())
+        };
+        // Keep the pivot - offset the position so that the local origin stays in place
+        self.position += self.matrix().transform_vector(pivot) - pivot;
+    }
 }
 
+/// Combines a parent transform (`self`) with a child transform (`rhs`) - the result maps points
+/// from the local space of the child to the space of the parent's parent.
+///
+/// > **Note:** skew (e.g. a rotated child of a non-uniformly scaled parent) can't be represented by
+/// > a `Transform` - use [Affine2](Affine2) multiplication if you need it.
 impl Add<&Transform> for Transform {
     type Output = Transform;
 
     fn add(self, rhs: &Transform) -> Self::Output {
         Self::Output {
-            position: self.position + rhs.position,
-            scale: self.scale * rhs.scale,
-            parent: self.parent,
+            hierarchy: Hierarchy {
+                parent: self.hierarchy.parent,
+                cache: Cell::default(),
+            },
+            ..Transform::from(self.matrix().mul(rhs.matrix()))
         }
     }
 }
 
+/// The inverse of `Add` - removes a parent transform (`rhs`) from an absolute transform (`self`).
 impl Sub<&Transform> for Transform {
     type Output = Transform;
 
     fn sub(self, rhs: &Transform) -> Self::Output {
+        // A zero-scaled parent collapses everything into a single point - there's nothing to undo
+        let rhs_inverse = rhs.matrix().inverse().unwrap_or_default();
         Self::Output {
-            position: self.position - rhs.position,
-            scale: self.scale / rhs.scale,
-            parent: self.parent,
+            hierarchy: Hierarchy {
+                parent: self.hierarchy.parent,
+                cache: Cell::default(),
+            },
+            ..Transform::from(rhs_inverse.mul(self.matrix()))
         }
     }
 }
@@ -68,7 +181,104 @@ impl Default for Transform {
         Self {
             position: Vertex2::default(),
             scale: DEFAULT_SCALE,
-            parent: None,
+            rotation: 0.0,
+            pivot: Vertex2::default(),
+            hierarchy: Hierarchy::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_near;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn child_orbits_rotated_parent() {
+        let parent = Transform {
+            rotation: FRAC_PI_2,
+            ..Transform::from_position(Vertex2::new(100.0, 100.0))
+        };
+        let child = Transform {
+            rotation: FRAC_PI_2,
+            ..Transform::from_position(Vertex2::new(0.0, 10.0))
+        };
+
+        let absolute = parent + &child;
+        assert_near(absolute.position, Vertex2::new(90.0, 100.0));
+        assert!((absolute.rotation.abs() - FRAC_PI_2 * 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn child_offset_is_scaled_by_parent() {
+        let parent = Transform {
+            scale: Vertex2::new(2.0, 3.0),
+            ..Transform::from_position(Vertex2::new(100.0, 100.0))
+        };
+        let child = Transform::from_position(Vertex2::new(10.0, 10.0));
+
+        let absolute = parent.clone() + &child;
+        assert_near(absolute.position, Vertex2::new(120.0, 130.0));
+        assert_near(absolute.scale, Vertex2::new(2.0, 3.0));
+
+        let relative = absolute - &parent;
+        assert_near(relative.position, child.position);
+        assert_near(relative.scale, child.scale);
+    }
+
+    #[test]
+    fn absolute() {
+        let parent = Transform {
+            scale: Vertex2::new(2.0, 2.0),
+            ..Transform::from_position(Vertex2::new(100.0, 100.0))
+        };
+        let mut child = Transform::from_position(Vertex2::new(10.0, -10.0));
+        child.set_parent_matrix(parent.matrix());
+
+        assert_near(child.absolute().position, Vertex2::new(120.0, 80.0));
+        assert_near(
+            child
+                .absolute_matrix()
+                .transform_point(Vertex2::new(1.0, 0.0)),
+            Vertex2::new(122.0, 80.0),
+        );
+    }
+
+    #[test]
+    fn set_absolute() {
+        let parent = Transform {
+            scale: Vertex2::new(2.0, 2.0),
+            rotation: FRAC_PI_2,
+            ..Transform::from_position(Vertex2::new(100.0, 100.0))
+        };
+        let mut child = Transform {
+            pivot: Vertex2::new(5.0, 0.0),
+            ..Transform::default()
+        };
+        child.set_parent_matrix(parent.matrix());
+
+        let mut absolute = child.absolute();
+        absolute.position = Vertex2::new(50.0, 60.0);
+        absolute.rotation = 1.0;
+        child.set_absolute(&absolute);
+        assert_eq!(child.parent_matrix(), parent.matrix());
+
+        let actual = child.absolute();
+        assert_near(actual.position, Vertex2::new(50.0, 60.0));
+        assert!((actual.rotation - 1.0).abs() < 0.0001);
+        assert_eq!(child.pivot, Vertex2::new(5.0, 0.0));
+    }
+
+    #[test]
+    fn absolute_cache_invalidation() {
+        let mut transform = Transform::from_position(Vertex2::new(10.0, 0.0));
+        assert_near(transform.absolute().position, Vertex2::new(10.0, 0.0));
+
+        transform.position.x = 20.0;
+        assert_near(transform.absolute().position, Vertex2::new(20.0, 0.0));
+
+        transform.set_parent_matrix(Affine2::from_translation(Vertex2::new(0.0, 5.0)));
+        assert_near(transform.absolute().position, Vertex2::new(20.0, 5.0));
+    }
+}
@@ -0,0 +1,95 @@
+use super::render_backend::RenderBackend;
+use crate::error::Error;
+use log::error;
+
+/// What the [Engine](crate::Engine) does after an [error handler](ErrorHandler) ran.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Keeps running the main loop
+    Continue,
+
+    /// Stops the engine for good, like [EngineHandle::stop](crate::EngineHandle::stop) - the last
+    /// frame stays on the canvas
+    Stop,
+}
+
+/// Called with the error and the canvas of the frame it happened in - the frame is already
+/// rendered, so anything drawn here (e.g. an error overlay) ends up on top of it.
+pub type ErrorHandler = dyn FnMut(&Error, &mut dyn RenderBackend) -> ErrorAction;
+
+/// Decides what happens when a frame of the [Engine](crate::Engine) fails - when a component's
+/// [try_update](crate::ComponentLifecycle::try_update) returns an error, or when an animation
+/// frame can't be requested. A failing request stops the engine regardless of the action, since
+/// no frame is pending - when it's the first request, [Engine::run](crate::Engine::run) or
+/// [EngineHandle::resume](crate::EngineHandle::resume) return the error as well.
+///
+/// A failing component doesn't stop the other components from updating, nor the frame from being
+/// rendered - only the first error of a frame is handed to the policy, the rest are logged.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{ErrorAction, ErrorPolicy, Vertex2};
+///
+/// // Shows the error on top of the last frame
+/// let policy = ErrorPolicy::Handler(Box::new(|err, backend| {
+///     backend.reset_transform();
+///     backend.set_fill_style("red");
+///     backend.set_font("16px sans-serif");
+///     backend.fill_text(&err.to_string(), Vertex2::new(10.0, 20.0), None);
+///     ErrorAction::Stop
+/// }));
+/// ```
+#[derive(Default)]
+pub enum ErrorPolicy {
+    /// Logs the error and keeps running
+    #[default]
+    Log,
+
+    /// Logs the error and stops the engine for good
+    Stop,
+
+    /// Hands the error to the handler, which decides whether the engine keeps running
+    Handler(Box<ErrorHandler>),
+}
+
+impl ErrorPolicy {
+    pub(crate) fn handle(&mut self, err: &Error, backend: &mut dyn RenderBackend) -> ErrorAction {
+        match self {
+            ErrorPolicy::Log => {
+                error!("{}", err);
+                ErrorAction::Continue
+            }
+            ErrorPolicy::Stop => {
+                error!("{}", err);
+                ErrorAction::Stop
+            }
+            ErrorPolicy::Handler(handler) => handler(err, backend),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RecordingBackend;
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn handler() {
+        let handled = Rc::new(Cell::new(false));
+        let handler_handled = handled.clone();
+        let mut policy = ErrorPolicy::Handler(Box::new(move |_, _| {
+            handler_handled.set(true);
+            ErrorAction::Stop
+        }));
+
+        let action = policy.handle(&"Failed".into(), &mut RecordingBackend::default());
+        assert_eq!(action, ErrorAction::Stop);
+        assert!(handled.get());
+        assert_eq!(
+            ErrorPolicy::default().handle(&"Failed".into(), &mut RecordingBackend::default()),
+            ErrorAction::Continue
+        );
+    }
+}
@@ -10,7 +10,7 @@
 //! Use the [Key](Key) enum for the `keyboard` methods and the [Mouse](Mouse) enum for the `mouse`
 //! methods
 
-mod event_listener;
+pub(super) mod event_listener;
 mod key_state_map;
 mod keyboard;
 mod mouse;
@@ -19,6 +19,7 @@ pub use keyboard::Button as Key;
 pub use mouse::Button as Mouse;
 
 use crate::error::Result;
+use event_listener::EventListener;
 use keyboard::Keyboard;
 use web_sys::Window;
 
@@ -29,10 +30,12 @@ pub struct Input {
 }
 
 impl Input {
-    #[doc(hidden)]
-    pub fn init(&self, window: &Window) -> Result<()> {
-        self.mouse.init(window)?;
-        self.keyboard.init(window)
+    /// Attaches the input event handlers to the window - they're detached when the returned
+    /// listeners are dropped.
+    pub(crate) fn init(&self, window: &Window) -> Result<Vec<EventListener>> {
+        let mut listeners = self.mouse.init(window)?;
+        listeners.extend(self.keyboard.init(window)?);
+        Ok(listeners)
     }
 
     #[doc(hidden)]
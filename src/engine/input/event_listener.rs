@@ -1,19 +1,45 @@
 use crate::error::Result;
+use std::any::Any;
 use wasm_bindgen::{JsCast, convert::FromWasmAbi, prelude::Closure};
-use web_sys::Window;
+use web_sys::{Window, js_sys::Function};
 
 pub type EventHandler<T> = Closure<dyn Fn(T)>;
 
+/// An attached event handler - it's detached from the window when it's dropped.
+pub struct EventListener {
+    window: Window,
+    event_name: String,
+    callback: Function,
+
+    /// Keeps the closure behind the `callback` alive
+    _handler: Box<dyn Any>,
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let result = self
+            .window
+            .remove_event_listener_with_callback(&self.event_name, &self.callback);
+        if result.is_err() {
+            log::warn!("Failed to detach {} event listener", self.event_name);
+        }
+    }
+}
+
 pub fn attach<T: FromWasmAbi + 'static>(
     window: &Window,
     event_name: &str,
     handler: EventHandler<T>,
-) -> Result<()> {
+) -> Result<EventListener> {
+    let callback: Function = handler.as_ref().unchecked_ref::<Function>().clone();
     window
-        .add_event_listener_with_callback(event_name, handler.as_ref().unchecked_ref())
+        .add_event_listener_with_callback(event_name, &callback)
         .map_err(|_| format!("Failed to attach {} event listener", event_name))?;
 
-    handler.forget();
-
-    Ok(())
+    Ok(EventListener {
+        window: window.clone(),
+        event_name: event_name.to_owned(),
+        callback,
+        _handler: Box::new(handler),
+    })
 }
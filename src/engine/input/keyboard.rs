@@ -1,5 +1,5 @@
 use super::{
-    event_listener::{self, EventHandler},
+    event_listener::{self, EventHandler, EventListener},
     key_state_map::KeyStateMap,
 };
 use crate::error::Result;
@@ -244,11 +244,11 @@ pub struct Keyboard {
 }
 
 impl Keyboard {
-    pub fn init(&self, window: &Window) -> Result<()> {
-        self.attach_key_up_handler(window)?;
-        self.attach_key_down_handler(window)?;
-
-        Ok(())
+    pub(crate) fn init(&self, window: &Window) -> Result<Vec<EventListener>> {
+        Ok(vec![
+            self.attach_key_up_handler(window)?,
+            self.attach_key_down_handler(window)?,
+        ])
     }
 
     pub fn is_pressed(&self, key: Button) -> bool {
@@ -271,7 +271,15 @@ impl Keyboard {
         self.state_map.borrow_mut().transition_states()
     }
 
-    fn attach_key_down_handler(&self, window: &Window) -> Result<()> {
+    pub(crate) fn handle_key_down(&self, key: Button) {
+        self.state_map.borrow_mut().handle_key_down(&key);
+    }
+
+    pub(crate) fn handle_key_up(&self, key: Button) {
+        self.state_map.borrow_mut().handle_key_up(&key);
+    }
+
+    fn attach_key_down_handler(&self, window: &Window) -> Result<EventListener> {
         let state_map = self.state_map.clone();
         event_listener::attach(
             window,
@@ -290,7 +298,7 @@ impl Keyboard {
         )
     }
 
-    fn attach_key_up_handler(&self, window: &Window) -> Result<()> {
+    fn attach_key_up_handler(&self, window: &Window) -> Result<EventListener> {
         let state_map = self.state_map.clone();
         event_listener::attach(
             window,
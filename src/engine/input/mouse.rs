@@ -1,8 +1,8 @@
 use super::{
-    event_listener::{self, EventHandler},
+    event_listener::{self, EventHandler, EventListener},
     key_state_map::KeyStateMap,
 };
-use crate::{Vertex2, error::Result};
+use crate::{ScreenLayout, Vertex2, error::Result};
 use log::warn;
 use std::{cell::RefCell, rc::Rc};
 use web_sys::{MouseEvent, Window};
@@ -30,18 +30,25 @@ impl Button {
 pub struct Mouse {
     state_map: Rc<RefCell<KeyStateMap<Button>>>,
     position: Rc<RefCell<Vertex2<i32>>>,
+    layout: ScreenLayout,
 }
 
 impl Mouse {
-    pub fn init(&self, window: &Window) -> Result<()> {
-        self.attach_mouse_up_handler(window)?;
-        self.attach_mouse_down_handler(window)?;
-        self.attach_mouse_move_handler(window)?;
-
-        Ok(())
+    pub(crate) fn init(&self, window: &Window) -> Result<Vec<EventListener>> {
+        Ok(vec![
+            self.attach_mouse_up_handler(window)?,
+            self.attach_mouse_down_handler(window)?,
+            self.attach_mouse_move_handler(window)?,
+        ])
     }
 
+    /// In logical pixels - see [Scaling](crate::Scaling).
     pub fn position(&self) -> Vertex2<f32> {
+        self.layout.to_logical(self.client_position())
+    }
+
+    /// In CSS pixels relative to the window, as reported by the browser.
+    pub fn client_position(&self) -> Vertex2<f32> {
         let position = self.position.borrow();
         Vertex2::new(position.x as f32, position.y as f32)
     }
@@ -66,7 +73,23 @@ impl Mouse {
         self.state_map.borrow_mut().transition_states()
     }
 
-    fn attach_mouse_down_handler(&self, window: &Window) -> Result<()> {
+    pub(crate) fn handle_key_down(&self, key: Button) {
+        self.state_map.borrow_mut().handle_key_down(&key);
+    }
+
+    pub(crate) fn handle_key_up(&self, key: Button) {
+        self.state_map.borrow_mut().handle_key_up(&key);
+    }
+
+    pub(crate) fn set_layout(&mut self, layout: ScreenLayout) {
+        self.layout = layout;
+    }
+
+    pub(crate) fn handle_move(&self, position: Vertex2<i32>) {
+        *self.position.borrow_mut() = position;
+    }
+
+    fn attach_mouse_down_handler(&self, window: &Window) -> Result<EventListener> {
         let state_map = self.state_map.clone();
         event_listener::attach(
             window,
@@ -85,7 +108,7 @@ impl Mouse {
         )
     }
 
-    fn attach_mouse_up_handler(&self, window: &Window) -> Result<()> {
+    fn attach_mouse_up_handler(&self, window: &Window) -> Result<EventListener> {
         let state_map = self.state_map.clone();
         event_listener::attach(
             window,
@@ -104,7 +127,7 @@ impl Mouse {
         )
     }
 
-    fn attach_mouse_move_handler(&self, window: &Window) -> Result<()> {
+    fn attach_mouse_move_handler(&self, window: &Window) -> Result<EventListener> {
         let position = self.position.clone();
         event_listener::attach(
             window,
@@ -117,3 +140,23 @@ impl Mouse {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scaling;
+
+    #[test]
+    fn logical_position() {
+        let mut mouse = Mouse::default();
+        mouse.set_layout(ScreenLayout::new(
+            Scaling::Fixed(Vertex2::new(400.0, 400.0)),
+            Vertex2::new(800.0, 600.0),
+            2.0,
+        ));
+        mouse.handle_move(Vertex2::new(400, 300));
+
+        assert_eq!(mouse.client_position(), Vertex2::new(400.0, 300.0));
+        assert_eq!(mouse.position(), Vertex2::new(200.0, 200.0));
+    }
+}
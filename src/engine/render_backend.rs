@@ -0,0 +1,91 @@
+//! The drawing API used by the engine - every draw call made while rendering a frame goes
+//! through a [RenderBackend](RenderBackend).
+//!
+//! The canvas is the backend used when running in the browser, while the
+//! [RecordingBackend](RecordingBackend) captures the draw calls as a list of
+//! [DrawCommand](DrawCommand)s so that a frame can be inspected without a browser.
+
+mod recording;
+
+pub use recording::{DrawCommand, RecordingBackend};
+
+use super::asset::{ImageHandle, ImageLoader};
+use crate::{
+    Affine2, FillRule, LineCap, LineJoin, TextAlign, TextBaseline, TextMeasurer, Vertex2,
+    dimensions::Dimensions,
+};
+use web_sys::CanvasRenderingContext2d;
+
+/// A subset of the canvas 2D API that the engine (and the custom [Style](crate::Style) closures)
+/// can draw with.
+///
+/// Every backend can measure text and load images as well, since it has to draw them (see
+/// [Context::measure_text](crate::Context::measure_text) and
+/// [AssetManager::load_image](crate::AssetManager::load_image)).
+pub trait RenderBackend: TextMeasurer + ImageLoader {
+    fn begin_path(&mut self);
+    fn move_to(&mut self, point: Vertex2<f32>);
+    fn line_to(&mut self, point: Vertex2<f32>);
+    fn quadratic_curve_to(&mut self, control: Vertex2<f32>, point: Vertex2<f32>);
+    fn bezier_curve_to(
+        &mut self,
+        control_1: Vertex2<f32>,
+        control_2: Vertex2<f32>,
+        point: Vertex2<f32>,
+    );
+    fn arc_to(&mut self, corner: Vertex2<f32>, point: Vertex2<f32>, radius: f32);
+    fn close_path(&mut self);
+
+    fn fill(&mut self, rule: FillRule);
+    fn stroke(&mut self);
+
+    fn set_fill_style(&mut self, style: &str);
+    fn set_stroke_style(&mut self, style: &str);
+    fn set_line_width(&mut self, width: f32);
+    fn set_line_join(&mut self, join: LineJoin);
+    fn set_line_cap(&mut self, cap: LineCap);
+    fn set_line_dash(&mut self, segments: &[f32]);
+    fn set_global_alpha(&mut self, alpha: f32);
+
+    /// Expects the font in the CSS shorthand format - see [Font::css](crate::Font::css).
+    fn set_font(&mut self, font: &str);
+    fn set_text_align(&mut self, align: TextAlign);
+    fn set_text_baseline(&mut self, baseline: TextBaseline);
+    fn fill_text(&mut self, text: &str, position: Vertex2<f32>, max_width: Option<f32>);
+    fn stroke_text(&mut self, text: &str, position: Vertex2<f32>, max_width: Option<f32>);
+
+    /// Draws the `source` rectangle of the image (in image pixels) into the `destination`
+    /// rectangle, optionally multiplied with the `tint` color.
+    fn draw_image(
+        &mut self,
+        image: ImageHandle,
+        source: &Dimensions,
+        destination: &Dimensions,
+        tint: Option<&str>,
+    );
+
+    fn translate(&mut self, offset: Vertex2<f32>);
+    fn rotate(&mut self, radians: f32);
+    fn scale(&mut self, scale: Vertex2<f32>);
+    fn set_transform(&mut self, matrix: &Affine2);
+    fn reset_transform(&mut self);
+
+    fn save(&mut self);
+    fn restore(&mut self);
+
+    /// Restricts drawing to the rectangle (in the current transform) until the state is
+    /// restored - see [save](Self::save) and [restore](Self::restore).
+    fn clip(&mut self, area: &Dimensions);
+
+    /// Clears the entire drawing surface.
+    fn clear(&mut self);
+
+    /// The size of the drawing surface in pixels - anything outside of it is not visible.
+    fn size(&self) -> Vertex2<f32>;
+
+    /// An escape hatch for canvas calls that are not covered by the trait - it's `None` for
+    /// backends that don't draw to a canvas (e.g. the [RecordingBackend](RecordingBackend)).
+    fn canvas_context(&self) -> Option<&CanvasRenderingContext2d> {
+        None
+    }
+}
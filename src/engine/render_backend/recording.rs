@@ -0,0 +1,356 @@
+use super::RenderBackend;
+use crate::{
+    Affine2, FillRule, Font, ImageHandle, ImageLoader, LineCap, LineJoin, LoadState, TextAlign,
+    TextBaseline, TextMeasurer, Vertex2, dimensions::Dimensions,
+};
+use std::collections::HashMap;
+
+/// A single draw call captured by the [RecordingBackend](RecordingBackend).
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    BeginPath,
+    MoveTo(Vertex2<f32>),
+    LineTo(Vertex2<f32>),
+    QuadraticCurveTo {
+        control: Vertex2<f32>,
+        point: Vertex2<f32>,
+    },
+    BezierCurveTo {
+        control_1: Vertex2<f32>,
+        control_2: Vertex2<f32>,
+        point: Vertex2<f32>,
+    },
+    ArcTo {
+        corner: Vertex2<f32>,
+        point: Vertex2<f32>,
+        radius: f32,
+    },
+    ClosePath,
+
+    Fill(FillRule),
+    Stroke,
+
+    SetFillStyle(String),
+    SetStrokeStyle(String),
+    SetLineWidth(f32),
+    SetLineJoin(LineJoin),
+    SetLineCap(LineCap),
+    SetLineDash(Vec<f32>),
+    SetGlobalAlpha(f32),
+
+    SetFont(String),
+    SetTextAlign(TextAlign),
+    SetTextBaseline(TextBaseline),
+    FillText {
+        text: String,
+        position: Vertex2<f32>,
+        max_width: Option<f32>,
+    },
+    StrokeText {
+        text: String,
+        position: Vertex2<f32>,
+        max_width: Option<f32>,
+    },
+
+    DrawImage {
+        image: ImageHandle,
+        source: Dimensions,
+        destination: Dimensions,
+        tint: Option<String>,
+    },
+
+    Translate(Vertex2<f32>),
+    Rotate(f32),
+    Scale(Vertex2<f32>),
+    SetTransform(Affine2),
+    ResetTransform,
+
+    Save,
+    Restore,
+    Clip(Dimensions),
+
+    Clear,
+}
+
+/// A headless backend - instead of drawing it records every call as a [DrawCommand](DrawCommand)
+/// so that tests can assert on what a frame draws.
+///
+/// Text is measured as if every character was half as wide as the font size. Images never finish
+/// loading on their own - use [set_image_size](Self::set_image_size) to fake a loaded image.
+///
+/// The drawing surface is `800x600` pixels unless it's changed with [set_size](Self::set_size).
+#[derive(Debug)]
+pub struct RecordingBackend {
+    commands: Vec<DrawCommand>,
+    size: Vertex2<f32>,
+
+    /// The path of every loaded image
+    images: HashMap<ImageHandle, String>,
+    image_sizes: HashMap<String, Vertex2<f32>>,
+}
+
+impl Default for RecordingBackend {
+    fn default() -> Self {
+        Self {
+            commands: Vec::default(),
+            size: Vertex2::new(800.0, 600.0),
+            images: HashMap::default(),
+            image_sizes: HashMap::default(),
+        }
+    }
+}
+
+impl RecordingBackend {
+    pub fn set_size(&mut self, size: Vertex2<f32>) {
+        self.size = size;
+    }
+
+    /// Makes the image at the path ready (whether it's loaded before or after the call).
+    pub fn set_image_size(&mut self, path: &str, size: Vertex2<f32>) {
+        self.image_sizes.insert(path.to_owned(), size);
+    }
+
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Returns the recorded commands and starts a fresh recording.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand> {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+impl ImageLoader for RecordingBackend {
+    fn load_image(&mut self, image: ImageHandle, path: &str) {
+        self.images.insert(image, path.to_owned());
+    }
+
+    fn image_state(&self, image: ImageHandle) -> LoadState {
+        match self.image_size(image) {
+            Some(_) => LoadState::Ready,
+            None => LoadState::Loading,
+        }
+    }
+
+    fn image_size(&self, image: ImageHandle) -> Option<Vertex2<f32>> {
+        let path = self.images.get(&image)?;
+        self.image_sizes.get(path).copied()
+    }
+}
+
+impl TextMeasurer for RecordingBackend {
+    fn measure_text(&self, text: &str, font: &Font) -> f32 {
+        text.chars().count() as f32 * font.size / 2.0
+    }
+}
+
+impl RenderBackend for RecordingBackend {
+    fn begin_path(&mut self) {
+        self.commands.push(DrawCommand::BeginPath);
+    }
+
+    fn move_to(&mut self, point: Vertex2<f32>) {
+        self.commands.push(DrawCommand::MoveTo(point));
+    }
+
+    fn line_to(&mut self, point: Vertex2<f32>) {
+        self.commands.push(DrawCommand::LineTo(point));
+    }
+
+    fn quadratic_curve_to(&mut self, control: Vertex2<f32>, point: Vertex2<f32>) {
+        self.commands
+            .push(DrawCommand::QuadraticCurveTo { control, point });
+    }
+
+    fn bezier_curve_to(
+        &mut self,
+        control_1: Vertex2<f32>,
+        control_2: Vertex2<f32>,
+        point: Vertex2<f32>,
+    ) {
+        self.commands.push(DrawCommand::BezierCurveTo {
+            control_1,
+            control_2,
+            point,
+        });
+    }
+
+    fn arc_to(&mut self, corner: Vertex2<f32>, point: Vertex2<f32>, radius: f32) {
+        self.commands.push(DrawCommand::ArcTo {
+            corner,
+            point,
+            radius,
+        });
+    }
+
+    fn close_path(&mut self) {
+        self.commands.push(DrawCommand::ClosePath);
+    }
+
+    fn fill(&mut self, rule: FillRule) {
+        self.commands.push(DrawCommand::Fill(rule));
+    }
+
+    fn stroke(&mut self) {
+        self.commands.push(DrawCommand::Stroke);
+    }
+
+    fn set_fill_style(&mut self, style: &str) {
+        self.commands
+            .push(DrawCommand::SetFillStyle(style.to_owned()));
+    }
+
+    fn set_stroke_style(&mut self, style: &str) {
+        self.commands
+            .push(DrawCommand::SetStrokeStyle(style.to_owned()));
+    }
+
+    fn set_line_width(&mut self, width: f32) {
+        self.commands.push(DrawCommand::SetLineWidth(width));
+    }
+
+    fn set_line_join(&mut self, join: LineJoin) {
+        self.commands.push(DrawCommand::SetLineJoin(join));
+    }
+
+    fn set_line_cap(&mut self, cap: LineCap) {
+        self.commands.push(DrawCommand::SetLineCap(cap));
+    }
+
+    fn set_line_dash(&mut self, segments: &[f32]) {
+        self.commands
+            .push(DrawCommand::SetLineDash(segments.to_vec()));
+    }
+
+    fn set_global_alpha(&mut self, alpha: f32) {
+        self.commands.push(DrawCommand::SetGlobalAlpha(alpha));
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.commands.push(DrawCommand::SetFont(font.to_owned()));
+    }
+
+    fn set_text_align(&mut self, align: TextAlign) {
+        self.commands.push(DrawCommand::SetTextAlign(align));
+    }
+
+    fn set_text_baseline(&mut self, baseline: TextBaseline) {
+        self.commands.push(DrawCommand::SetTextBaseline(baseline));
+    }
+
+    fn fill_text(&mut self, text: &str, position: Vertex2<f32>, max_width: Option<f32>) {
+        self.commands.push(DrawCommand::FillText {
+            text: text.to_owned(),
+            position,
+            max_width,
+        });
+    }
+
+    fn stroke_text(&mut self, text: &str, position: Vertex2<f32>, max_width: Option<f32>) {
+        self.commands.push(DrawCommand::StrokeText {
+            text: text.to_owned(),
+            position,
+            max_width,
+        });
+    }
+
+    fn draw_image(
+        &mut self,
+        image: ImageHandle,
+        source: &Dimensions,
+        destination: &Dimensions,
+        tint: Option<&str>,
+    ) {
+        self.commands.push(DrawCommand::DrawImage {
+            image,
+            source: *source,
+            destination: *destination,
+            tint: tint.map(str::to_owned),
+        });
+    }
+
+    fn translate(&mut self, offset: Vertex2<f32>) {
+        self.commands.push(DrawCommand::Translate(offset));
+    }
+
+    fn rotate(&mut self, radians: f32) {
+        self.commands.push(DrawCommand::Rotate(radians));
+    }
+
+    fn scale(&mut self, scale: Vertex2<f32>) {
+        self.commands.push(DrawCommand::Scale(scale));
+    }
+
+    fn set_transform(&mut self, matrix: &Affine2) {
+        self.commands.push(DrawCommand::SetTransform(*matrix));
+    }
+
+    fn reset_transform(&mut self) {
+        self.commands.push(DrawCommand::ResetTransform);
+    }
+
+    fn save(&mut self) {
+        self.commands.push(DrawCommand::Save);
+    }
+
+    fn restore(&mut self) {
+        self.commands.push(DrawCommand::Restore);
+    }
+
+    fn clip(&mut self, area: &Dimensions) {
+        self.commands.push(DrawCommand::Clip(*area));
+    }
+
+    fn clear(&mut self) {
+        self.commands.push(DrawCommand::Clear);
+    }
+
+    fn size(&self) -> Vertex2<f32> {
+        self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn images() {
+        let mut backend = RecordingBackend::default();
+        let image = ImageHandle::new(0, 0);
+        backend.load_image(image, "player.png");
+        assert_eq!(backend.image_state(image), LoadState::Loading);
+
+        backend.set_image_size("player.png", Vertex2::new(16.0, 16.0));
+        assert_eq!(backend.image_state(image), LoadState::Ready);
+        assert_eq!(backend.image_size(image), Some(Vertex2::new(16.0, 16.0)));
+    }
+
+    #[test]
+    fn records_in_order() {
+        let mut backend = RecordingBackend::default();
+        backend.begin_path();
+        backend.move_to(Vertex2::new(1.0, 2.0));
+        backend.set_fill_style("red");
+        backend.fill(FillRule::NonZero);
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::BeginPath,
+                DrawCommand::MoveTo(Vertex2::new(1.0, 2.0)),
+                DrawCommand::SetFillStyle("red".to_owned()),
+                DrawCommand::Fill(FillRule::NonZero),
+            ]
+        );
+    }
+
+    #[test]
+    fn take_commands() {
+        let mut backend = RecordingBackend::default();
+        backend.clear();
+
+        assert_eq!(backend.take_commands(), vec![DrawCommand::Clear]);
+        assert!(backend.commands().is_empty());
+    }
+}
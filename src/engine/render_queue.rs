@@ -0,0 +1,183 @@
+use super::{
+    component::{Component, Renderable},
+    render_backend::RenderBackend,
+    renderer,
+    screen::{self, ScreenLayout},
+    viewport::Viewport,
+};
+use crate::Affine2;
+use std::cmp::Reverse;
+
+struct RenderItem<'a> {
+    renderable: &'a Renderable,
+    matrix: Affine2,
+}
+
+/// All of the renderables of a frame along with their absolute matrices - collected in a single
+/// traversal of the component tree and sorted back to front.
+#[derive(Default)]
+pub struct RenderQueue<'a> {
+    items: Vec<RenderItem<'a>>,
+}
+
+impl<'a> RenderQueue<'a> {
+    pub fn new(components: &[&'a dyn Component]) -> Self {
+        let mut queue = RenderQueue::default();
+        queue.collect(components);
+        queue.sort();
+        queue
+    }
+
+    /// Draws the items once per viewport, through the view of its camera - except for the ones
+    /// in the camera's screen layers, which are drawn relative to the viewport. Items are
+    /// skipped in the viewports that are not in their mask.
+    ///
+    /// Everything is scaled from logical pixels to the pixels of the backend by the layout.
+    pub fn draw(
+        &self,
+        backend: &mut dyn RenderBackend,
+        viewports: &[Viewport],
+        layout: &ScreenLayout,
+    ) {
+        let logical = layout.matrix();
+        for (index, viewport) in viewports.iter().enumerate() {
+            let clipped = layout.is_letterboxed() || !viewport.is_full_screen();
+            if clipped {
+                backend.save();
+                backend.reset_transform();
+                backend.clip(&screen::transform_area(&logical, &viewport.screen_area()));
+            }
+
+            let screen = logical * Affine2::from_translation(viewport.offset());
+            let view = screen * viewport.camera.view_matrix();
+            for item in &self.items {
+                if !item.renderable.viewports.contains(index) {
+                    continue;
+                }
+
+                if viewport.camera.is_screen_layer(item.renderable.layer) {
+                    renderer::draw(backend, item.renderable, &(screen * item.matrix));
+                } else {
+                    renderer::draw(backend, item.renderable, &(view * item.matrix));
+                }
+            }
+
+            if clipped {
+                backend.restore();
+            }
+        }
+    }
+
+    fn collect(&mut self, components: &[&'a dyn Component]) {
+        // The renderables of all siblings are collected before descending into their children -
+        // this is the order renderables within the same layer are drawn in.
+        for component in components {
+            let component_matrix = component.transform().absolute_matrix();
+            for renderable in component.renderables() {
+                self.items.push(RenderItem {
+                    renderable,
+                    matrix: component_matrix * renderable.transform.matrix(),
+                });
+            }
+        }
+
+        for component in components {
+            self.collect(&component.children());
+        }
+    }
+
+    /// Lower layers have higher priority, so they are drawn last. The sort is stable - items in
+    /// the same layer keep their traversal order.
+    fn sort(&mut self) {
+        self.items
+            .sort_by_key(|item| Reverse(item.renderable.layer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentLifecycle, Context, Layer, Transform, shape_factory};
+    use twors_derive::Component;
+
+    #[derive(Component)]
+    struct Node {
+        transform: Transform,
+        renderables: Vec<Renderable>,
+        #[children]
+        children: Vec<Node>,
+    }
+
+    impl Node {
+        fn new(layers: &[Layer], children: Vec<Node>) -> Self {
+            let renderables = layers
+                .iter()
+                .map(|layer| Renderable::new(shape_factory::square(1.0), *layer))
+                .collect();
+
+            Self {
+                transform: Transform::default(),
+                renderables,
+                children,
+            }
+        }
+    }
+
+    impl ComponentLifecycle for Node {
+        fn update(&mut self, _: &mut Context) {}
+    }
+
+    #[test]
+    fn sorted_back_to_front() {
+        let child = Node::new(&[Layer::Five, Layer::One], Vec::default());
+        let root = Node::new(&[Layer::Two, Layer::Four], vec![child]);
+
+        let queue = RenderQueue::new(&[&root]);
+        let layers: Vec<Layer> = queue
+            .items
+            .iter()
+            .map(|item| item.renderable.layer)
+            .collect();
+        assert_eq!(layers, [Layer::Five, Layer::Four, Layer::Two, Layer::One]);
+    }
+
+    #[test]
+    fn arbitrary_layers() {
+        let root = Node::new(
+            &[Layer(-10), Layer(100), Layer::Three, Layer(7)],
+            Vec::default(),
+        );
+
+        let queue = RenderQueue::new(&[&root]);
+        let layers: Vec<Layer> = queue
+            .items
+            .iter()
+            .map(|item| item.renderable.layer)
+            .collect();
+        assert_eq!(layers, [Layer(100), Layer(7), Layer(3), Layer(-10)]);
+    }
+
+    #[test]
+    fn stable_within_layer() {
+        let grandchild = Node::new(&[Layer::One], Vec::default());
+        let first = Node::new(&[Layer::One], vec![grandchild]);
+        let second = Node::new(&[Layer::One], Vec::default());
+
+        let queue = RenderQueue::new(&[&first, &second]);
+        let renderables: Vec<*const Renderable> = queue
+            .items
+            .iter()
+            .map(|item| item.renderable as *const _)
+            .collect();
+
+        // Siblings first, then the children of the siblings
+        assert_eq!(
+            renderables,
+            [
+                &first.renderables[0] as *const _,
+                &second.renderables[0] as *const _,
+                &first.children[0].renderables[0] as *const _,
+            ]
+        );
+    }
+}
@@ -1,22 +1,653 @@
-use crate::{Transform, Vertex2, wasm_assert};
-use web_sys::CanvasRenderingContext2d;
+use super::{component::Shape, render_backend::RenderBackend};
+use crate::{
+    Affine2, Path, Renderable, Segment, Sprite, Style, Text, Vertex2, dimensions::Dimensions,
+    tilemap::TileLayer,
+};
 
-pub fn render(ctx: &CanvasRenderingContext2d, vertices: &[Vertex2<f32>], transform: &Transform) {
-    wasm_assert!(vertices.len() > 2);
+/// The number of lines an arc is split into when it can't be drawn natively.
+const ARC_SEGMENTS: usize = 16;
 
-    ctx.begin_path();
+pub fn draw(backend: &mut dyn RenderBackend, renderable: &Renderable, matrix: &Affine2) {
+    match &renderable.shape {
+        Shape::Path(path) => {
+            render(backend, path, matrix);
+            apply_style(backend, &renderable.style);
+        }
+        Shape::Text(text) => render_text(backend, text, &renderable.style, matrix),
+        Shape::Sprite(sprite) => render_sprite(backend, sprite, &renderable.style, matrix),
+        Shape::Tiles(layer) => render_tiles(backend, layer, &renderable.style, matrix),
+    }
+}
+
+pub fn render(backend: &mut dyn RenderBackend, path: &Path, matrix: &Affine2) {
+    backend.begin_path();
+
+    let arc_scale = similarity_scale(matrix);
+    for contour in &path.contours {
+        // A lone point (e.g. `Contour::open(vec![])`) has nothing to draw
+        if contour.segments.is_empty() {
+            continue;
+        }
+
+        backend.move_to(matrix.transform_point(contour.start));
+        let mut current = contour.start;
+        for segment in &contour.segments {
+            match *segment {
+                Segment::Line { to } => backend.line_to(matrix.transform_point(to)),
+                Segment::Quadratic { control, to } => backend.quadratic_curve_to(
+                    matrix.transform_point(control),
+                    matrix.transform_point(to),
+                ),
+                Segment::Cubic {
+                    control_1,
+                    control_2,
+                    to,
+                } => backend.bezier_curve_to(
+                    matrix.transform_point(control_1),
+                    matrix.transform_point(control_2),
+                    matrix.transform_point(to),
+                ),
+                Segment::ArcTo { corner, to, radius } => match arc_scale {
+                    Some(scale) => backend.arc_to(
+                        matrix.transform_point(corner),
+                        matrix.transform_point(to),
+                        radius * scale,
+                    ),
+                    // A non-uniformly scaled (or skewed) circle is an ellipse, which `arcTo`
+                    // can't draw
+                    None => {
+                        let mut vertices = Vec::default();
+                        segment.flatten_into(current, ARC_SEGMENTS, &mut vertices);
+                        for vertex in vertices {
+                            backend.line_to(matrix.transform_point(vertex));
+                        }
+                    }
+                },
+            }
+            current = segment.end(current);
+        }
+
+        if contour.closed {
+            backend.close_path();
+        }
+    }
+}
+
+/// The scale factor of a matrix that keeps circles circular (rotation, translation, uniform scale
+/// and mirroring only) - `None` for any other matrix.
+fn similarity_scale(matrix: &Affine2) -> Option<f32> {
+    const EPSILON: f32 = 0.0001;
+
+    let x_axis = matrix.transform_vector(Vertex2::new(1.0, 0.0));
+    let y_axis = matrix.transform_vector(Vertex2::new(0.0, 1.0));
+    let scale = x_axis.length();
+    let is_similarity = (scale - y_axis.length()).abs() < EPSILON * scale.max(1.0)
+        && x_axis.dot(y_axis).abs() < EPSILON;
+    is_similarity.then_some(scale)
+}
+
+pub fn apply_style(backend: &mut dyn RenderBackend, style: &Style) {
+    backend.set_global_alpha(style.alpha);
+
+    if let Some(fill) = &style.fill {
+        backend.set_fill_style(fill);
+        backend.fill(style.fill_rule);
+    }
+
+    if let Some(stroke) = &style.stroke {
+        set_stroke_style(backend, stroke, style);
+        backend.stroke();
+    }
+
+    if let Some(custom) = &style.custom {
+        custom(backend);
+    }
+}
+
+/// Text is drawn in its local space - the canvas transform is set to the matrix of the renderable
+/// (and restored afterwards) since the glyphs can't be transformed point by point.
+pub fn render_text(backend: &mut dyn RenderBackend, text: &Text, style: &Style, matrix: &Affine2) {
+    let lines = text.lines(backend);
+
+    backend.save();
+    backend.set_transform(matrix);
+    backend.set_global_alpha(style.alpha);
+    backend.set_font(&text.font.css());
+    backend.set_text_align(text.align);
+    backend.set_text_baseline(text.baseline);
+
+    if let Some(fill) = &style.fill {
+        backend.set_fill_style(fill);
+        for (line, y) in lines.iter().zip(text.line_offsets(lines.len())) {
+            backend.fill_text(line, Vertex2::new(0.0, y), text.max_width);
+        }
+    }
+
+    if let Some(stroke) = &style.stroke {
+        set_stroke_style(backend, stroke, style);
+        for (line, y) in lines.iter().zip(text.line_offsets(lines.len())) {
+            backend.stroke_text(line, Vertex2::new(0.0, y), text.max_width);
+        }
+    }
 
-    let start_vertex = vertices.first().unwrap();
-    let start_vertex = apply_transform(*start_vertex, transform);
+    if let Some(custom) = &style.custom {
+        custom(backend);
+    }
+
+    backend.restore();
+}
+
+/// Like text, sprites are drawn in their local space via the canvas transform.
+pub fn render_sprite(
+    backend: &mut dyn RenderBackend,
+    sprite: &Sprite,
+    style: &Style,
+    matrix: &Affine2,
+) {
+    let Some(image_size) = backend.image_size(sprite.image) else {
+        return;
+    };
+    let (source, destination) = sprite.layout(image_size);
+    let flip = Vertex2::new(
+        if sprite.flip_x { -1.0 } else { 1.0 },
+        if sprite.flip_y { -1.0 } else { 1.0 },
+    );
+
+    backend.save();
+    backend.set_transform(&(matrix * &Affine2::from_scale(flip)));
+    backend.set_global_alpha(style.alpha);
+    backend.draw_image(sprite.image, &source, &destination, sprite.tint.as_deref());
+    backend.restore();
+}
+
+/// Only the chunks that overlap the drawing surface are drawn. Like sprites, the tiles are drawn
+/// in the local space of the layer and only once their tileset image has loaded.
+pub fn render_tiles(
+    backend: &mut dyn RenderBackend,
+    layer: &TileLayer,
+    style: &Style,
+    matrix: &Affine2,
+) {
+    // Swaps the `x` and `y` axes
+    const DIAGONAL_FLIP: Affine2 = Affine2::new(0.0, 1.0, 1.0, 0.0, 0.0, 0.0);
 
-    ctx.move_to(start_vertex.x.into(), start_vertex.y.into());
-    for curr_vertex in vertices {
-        let curr_vertex = apply_transform(*curr_vertex, transform);
-        ctx.line_to(curr_vertex.x.into(), curr_vertex.y.into());
+    let Some(area) = visible_area(backend.size(), matrix) else {
+        return;
+    };
+    if !layer.visible {
+        return;
     }
-    ctx.line_to(start_vertex.x.into(), start_vertex.y.into());
+
+    // Tiles that are bigger than the cells stick out of them (up and to the right), so their
+    // cells can be visible even when they're outside of the area
+    let cell = layer.tile_size();
+    let overhang =
+        layer
+            .tilesets()
+            .iter()
+            .fold(Vertex2::default(), |overhang: Vertex2<f32>, tileset| {
+                Vertex2::new(
+                    overhang.x.max(tileset.tile_size.x - cell.x),
+                    overhang.y.max(tileset.tile_size.y - cell.y),
+                )
+            });
+    let area = Dimensions::new(
+        area.position(),
+        area.width() + overhang.x * 2.0,
+        area.height() + overhang.y * 2.0,
+    );
+
+    let loaded: Vec<bool> = layer
+        .tilesets()
+        .iter()
+        .map(|tileset| backend.image_size(tileset.image).is_some())
+        .collect();
+
+    backend.save();
+    backend.set_transform(matrix);
+    backend.set_global_alpha(style.alpha);
+    for (column, row) in layer.visible_chunks(&area) {
+        for (x, y, tile) in layer.chunk_tiles(column, row) {
+            if !loaded[tile.tileset] {
+                continue;
+            }
+
+            let tileset = &layer.tilesets()[tile.tileset];
+            let source = tileset.source(tileset.animated_tile(tile.index, layer.time()));
+            let size = tileset.tile_size;
+            // The bottom left corner of the tile is aligned with the bottom left of its cell
+            let center = Vertex2::new(
+                x as f32 * cell.x + size.x / 2.0,
+                (y + 1) as f32 * cell.y - size.y / 2.0,
+            );
+
+            if !tile.flip_x && !tile.flip_y && !tile.flip_diagonal {
+                let destination = Dimensions::new(center, size.x, size.y);
+                backend.draw_image(tileset.image, &source, &destination, None);
+                continue;
+            }
+
+            let flip = Vertex2::new(
+                if tile.flip_x { -1.0 } else { 1.0 },
+                if tile.flip_y { -1.0 } else { 1.0 },
+            );
+            let mut tile_matrix =
+                *matrix * Affine2::from_translation(center) * Affine2::from_scale(flip);
+            if tile.flip_diagonal {
+                tile_matrix *= DIAGONAL_FLIP;
+            }
+
+            backend.set_transform(&tile_matrix);
+            let destination = Dimensions::new(Vertex2::default(), size.x, size.y);
+            backend.draw_image(tileset.image, &source, &destination, None);
+            backend.set_transform(matrix);
+        }
+    }
+    backend.restore();
+}
+
+/// The bounding box of the drawing surface in the local space of the matrix - `None` if the
+/// matrix can't be inverted (nothing drawn with it is visible).
+fn visible_area(size: Vertex2<f32>, matrix: &Affine2) -> Option<Dimensions> {
+    let inverse = matrix.inverse()?;
+    let corners = [
+        Vertex2::new(0.0, 0.0),
+        Vertex2::new(size.x, 0.0),
+        Vertex2::new(0.0, size.y),
+        Vertex2::new(size.x, size.y),
+    ]
+    .map(|corner| inverse.transform_point(corner));
+
+    let min = corners
+        .into_iter()
+        .reduce(|min, corner| Vertex2::new(min.x.min(corner.x), min.y.min(corner.y)))?;
+    let max = corners
+        .into_iter()
+        .reduce(|max, corner| Vertex2::new(max.x.max(corner.x), max.y.max(corner.y)))?;
+    Some(Dimensions::from_top_left(min, max.x - min.x, max.y - min.y))
+}
+
+fn set_stroke_style(backend: &mut dyn RenderBackend, stroke: &str, style: &Style) {
+    backend.set_stroke_style(stroke);
+    backend.set_line_width(style.line_width);
+    backend.set_line_join(style.line_join);
+    backend.set_line_cap(style.line_cap);
+    backend.set_line_dash(&style.line_dash);
 }
 
-fn apply_transform(vertex: Vertex2<f32>, transform: &Transform) -> Vertex2<f32> {
-    (vertex * transform.scale) + transform.position
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Contour, FillRule, Font, ImageHandle, ImageLoader, LineCap, LineJoin, TextAlign,
+        TextBaseline, Transform,
+        dimensions::Dimensions,
+        engine::render_backend::{DrawCommand, RecordingBackend},
+        tilemap::{Tile, Tileset},
+    };
+    use std::rc::Rc;
+
+    #[test]
+    fn render_closed_path() {
+        let mut backend = RecordingBackend::default();
+        let path = Path::polygon(vec![
+            Vertex2::new(0.0, 0.0),
+            Vertex2::new(1.0, 0.0),
+            Vertex2::new(1.0, 1.0),
+        ]);
+        let transform = Transform {
+            scale: Vertex2::new(2.0, 2.0),
+            ..Transform::from_position(Vertex2::new(10.0, 20.0))
+        };
+
+        render(&mut backend, &path, &transform.matrix());
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::BeginPath,
+                DrawCommand::MoveTo(Vertex2::new(10.0, 20.0)),
+                DrawCommand::LineTo(Vertex2::new(12.0, 20.0)),
+                DrawCommand::LineTo(Vertex2::new(12.0, 22.0)),
+                DrawCommand::ClosePath,
+            ]
+        );
+    }
+
+    #[test]
+    fn render_multiple_contours() {
+        let mut backend = RecordingBackend::default();
+        let line = vec![Vertex2::new(0.0, 0.0), Vertex2::new(1.0, 0.0)];
+        let triangle = vec![
+            Vertex2::new(0.0, 0.0),
+            Vertex2::new(1.0, 0.0),
+            Vertex2::new(1.0, 1.0),
+        ];
+        let path = Path::polyline(line).with_polygon(triangle);
+
+        render(&mut backend, &path, &Affine2::IDENTITY);
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::BeginPath,
+                DrawCommand::MoveTo(Vertex2::new(0.0, 0.0)),
+                DrawCommand::LineTo(Vertex2::new(1.0, 0.0)),
+                DrawCommand::MoveTo(Vertex2::new(0.0, 0.0)),
+                DrawCommand::LineTo(Vertex2::new(1.0, 0.0)),
+                DrawCommand::LineTo(Vertex2::new(1.0, 1.0)),
+                DrawCommand::ClosePath,
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_empty_contours() {
+        let mut backend = RecordingBackend::default();
+        let line = vec![Vertex2::new(0.0, 0.0), Vertex2::new(1.0, 0.0)];
+        let path = Path::default()
+            .with_contour(Contour::open(Vec::default()))
+            .with_polyline(line)
+            .with_polygon(vec![Vertex2::new(5.0, 5.0)]);
+
+        render(&mut backend, &path, &Affine2::IDENTITY);
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::BeginPath,
+                DrawCommand::MoveTo(Vertex2::new(0.0, 0.0)),
+                DrawCommand::LineTo(Vertex2::new(1.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_curves() {
+        let mut backend = RecordingBackend::default();
+        let contour = Contour::new(Vertex2::new(0.0, 0.0))
+            .quadratic_to(Vertex2::new(1.0, 1.0), Vertex2::new(2.0, 0.0))
+            .cubic_to(
+                Vertex2::new(3.0, 1.0),
+                Vertex2::new(4.0, 1.0),
+                Vertex2::new(5.0, 0.0),
+            )
+            .arc_to(Vertex2::new(5.0, 5.0), Vertex2::new(0.0, 5.0), 1.0);
+        let transform = Transform {
+            scale: Vertex2::new(2.0, 2.0),
+            ..Transform::from_position(Vertex2::new(10.0, 20.0))
+        };
+
+        render(&mut backend, &contour.into(), &transform.matrix());
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::BeginPath,
+                DrawCommand::MoveTo(Vertex2::new(10.0, 20.0)),
+                DrawCommand::QuadraticCurveTo {
+                    control: Vertex2::new(12.0, 22.0),
+                    point: Vertex2::new(14.0, 20.0),
+                },
+                DrawCommand::BezierCurveTo {
+                    control_1: Vertex2::new(16.0, 22.0),
+                    control_2: Vertex2::new(18.0, 22.0),
+                    point: Vertex2::new(20.0, 20.0),
+                },
+                DrawCommand::ArcTo {
+                    corner: Vertex2::new(20.0, 30.0),
+                    point: Vertex2::new(10.0, 30.0),
+                    radius: 2.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn render_non_uniformly_scaled_arc() {
+        let mut backend = RecordingBackend::default();
+        let contour = Contour::new(Vertex2::new(0.0, 0.0)).arc_to(
+            Vertex2::new(2.0, 0.0),
+            Vertex2::new(2.0, 2.0),
+            1.0,
+        );
+
+        render(
+            &mut backend,
+            &contour.into(),
+            &Affine2::from_scale(Vertex2::new(2.0, 1.0)),
+        );
+
+        let commands = backend.commands();
+        assert_eq!(commands.len(), 2 + 1 + ARC_SEGMENTS);
+        assert_eq!(commands[2], DrawCommand::LineTo(Vertex2::new(2.0, 0.0)));
+        assert!(
+            commands[2..]
+                .iter()
+                .all(|command| matches!(command, DrawCommand::LineTo(_)))
+        );
+    }
+
+    #[test]
+    fn apply_fill_and_stroke() {
+        let mut backend = RecordingBackend::default();
+        let style = Style::filled("orange")
+            .with_stroke("black")
+            .with_line_width(2.0)
+            .with_alpha(0.5);
+
+        apply_style(&mut backend, &style);
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::SetGlobalAlpha(0.5),
+                DrawCommand::SetFillStyle("orange".to_owned()),
+                DrawCommand::Fill(FillRule::NonZero),
+                DrawCommand::SetStrokeStyle("black".to_owned()),
+                DrawCommand::SetLineWidth(2.0),
+                DrawCommand::SetLineJoin(LineJoin::Miter),
+                DrawCommand::SetLineCap(LineCap::Butt),
+                DrawCommand::SetLineDash(Vec::default()),
+                DrawCommand::Stroke,
+            ]
+        );
+    }
+
+    #[test]
+    fn render_wrapped_text() {
+        let mut backend = RecordingBackend::default();
+        let text = Text::new("Score: 100")
+            .with_font(Font::new("monospace", 10.0))
+            .with_max_width(40.0)
+            .with_line_height(2.0);
+        let matrix = Affine2::from_translation(Vertex2::new(5.0, 5.0));
+
+        render_text(&mut backend, &text, &Style::filled("white"), &matrix);
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::Save,
+                DrawCommand::SetTransform(matrix),
+                DrawCommand::SetGlobalAlpha(1.0),
+                DrawCommand::SetFont("400 10px monospace".to_owned()),
+                DrawCommand::SetTextAlign(TextAlign::Left),
+                DrawCommand::SetTextBaseline(TextBaseline::Alphabetic),
+                DrawCommand::SetFillStyle("white".to_owned()),
+                DrawCommand::FillText {
+                    text: "Score:".to_owned(),
+                    position: Vertex2::new(0.0, 0.0),
+                    max_width: Some(40.0),
+                },
+                DrawCommand::FillText {
+                    text: "100".to_owned(),
+                    position: Vertex2::new(0.0, 20.0),
+                    max_width: Some(40.0),
+                },
+                DrawCommand::Restore,
+            ]
+        );
+    }
+
+    #[test]
+    fn render_flipped_sprite() {
+        let mut backend = RecordingBackend::default();
+        let image = ImageHandle::new(0, 0);
+        backend.load_image(image, "player.png");
+        backend.set_image_size("player.png", Vertex2::new(20.0, 10.0));
+        let sprite = Sprite::new(image).with_flip_x(true).with_tint("red");
+        let matrix = Affine2::from_translation(Vertex2::new(5.0, 5.0));
+
+        render_sprite(
+            &mut backend,
+            &sprite,
+            &Style::default().with_alpha(0.5),
+            &matrix,
+        );
+
+        assert_eq!(
+            backend.commands(),
+            &[
+                DrawCommand::Save,
+                DrawCommand::SetTransform(Affine2::new(-1.0, 0.0, 0.0, 1.0, 5.0, 5.0)),
+                DrawCommand::SetGlobalAlpha(0.5),
+                DrawCommand::DrawImage {
+                    image,
+                    source: Dimensions::from_top_left(Vertex2::new(0.0, 0.0), 20.0, 10.0),
+                    destination: Dimensions::new(Vertex2::new(0.0, 0.0), 20.0, 10.0),
+                    tint: Some("red".to_owned()),
+                },
+                DrawCommand::Restore,
+            ]
+        );
+    }
+
+    #[test]
+    fn sprite_is_not_rendered_while_loading() {
+        let mut backend = RecordingBackend::default();
+        let image = ImageHandle::new(0, 0);
+        backend.load_image(image, "player.png");
+        let sprite = Sprite::new(image);
+
+        render_sprite(&mut backend, &sprite, &Style::default(), &Affine2::IDENTITY);
+
+        assert!(backend.commands().is_empty());
+    }
+
+    fn tile_layer(backend: &mut RecordingBackend) -> TileLayer {
+        let image = ImageHandle::new(0, 0);
+        backend.load_image(image, "tiles.png");
+        backend.set_image_size("tiles.png", Vertex2::new(20.0, 20.0));
+        let tileset = Tileset::new(image, Vertex2::new(10.0, 10.0), 2, 4);
+
+        TileLayer::new(
+            "ground",
+            40,
+            40,
+            Vertex2::new(10.0, 10.0),
+            Rc::from([tileset]),
+        )
+    }
+
+    fn drawn_destinations(backend: &RecordingBackend) -> Vec<Dimensions> {
+        backend
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::DrawImage { destination, .. } => Some(*destination),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn render_visible_tiles() {
+        let mut backend = RecordingBackend::default();
+        backend.set_size(Vertex2::new(100.0, 100.0));
+        let mut layer = tile_layer(&mut backend);
+        layer.set(0, 0, Some(Tile::new(0, 3))).unwrap();
+        layer.set(39, 39, Some(Tile::new(0, 1))).unwrap();
+
+        render_tiles(&mut backend, &layer, &Style::default(), &Affine2::IDENTITY);
+        assert_eq!(
+            backend.take_commands(),
+            [
+                DrawCommand::Save,
+                DrawCommand::SetTransform(Affine2::IDENTITY),
+                DrawCommand::SetGlobalAlpha(1.0),
+                DrawCommand::DrawImage {
+                    image: ImageHandle::new(0, 0),
+                    source: Dimensions::from_top_left(Vertex2::new(10.0, 10.0), 10.0, 10.0),
+                    destination: Dimensions::from_top_left(Vertex2::new(0.0, 0.0), 10.0, 10.0),
+                    tint: None,
+                },
+                DrawCommand::Restore,
+            ]
+        );
+
+        // Scrolled to the bottom right corner of the layer
+        let matrix = Affine2::from_translation(Vertex2::new(-320.0, -320.0));
+        render_tiles(&mut backend, &layer, &Style::default(), &matrix);
+        assert_eq!(
+            drawn_destinations(&backend),
+            [Dimensions::from_top_left(
+                Vertex2::new(390.0, 390.0),
+                10.0,
+                10.0
+            )]
+        );
+    }
+
+    #[test]
+    fn render_flipped_tile() {
+        let mut backend = RecordingBackend::default();
+        let mut layer = tile_layer(&mut backend);
+        let tile = Tile {
+            flip_x: true,
+            flip_diagonal: true,
+            ..Tile::new(0, 0)
+        };
+        layer.set(1, 0, Some(tile)).unwrap();
+
+        render_tiles(&mut backend, &layer, &Style::default(), &Affine2::IDENTITY);
+        assert_eq!(
+            backend.commands()[3..6],
+            [
+                // Rotated by 90 degrees clockwise around the center of the cell
+                DrawCommand::SetTransform(Affine2::new(0.0, 1.0, -1.0, 0.0, 15.0, 5.0)),
+                DrawCommand::DrawImage {
+                    image: ImageHandle::new(0, 0),
+                    source: Dimensions::from_top_left(Vertex2::new(0.0, 0.0), 10.0, 10.0),
+                    destination: Dimensions::new(Vertex2::new(0.0, 0.0), 10.0, 10.0),
+                    tint: None,
+                },
+                DrawCommand::SetTransform(Affine2::IDENTITY),
+            ]
+        );
+    }
+
+    #[test]
+    fn hidden_tile_layer() {
+        let mut backend = RecordingBackend::default();
+        let mut layer = tile_layer(&mut backend);
+        layer.set(0, 0, Some(Tile::new(0, 0))).unwrap();
+        layer.visible = false;
+
+        render_tiles(&mut backend, &layer, &Style::default(), &Affine2::IDENTITY);
+        assert!(backend.commands().is_empty());
+    }
+
+    #[test]
+    fn apply_custom() {
+        let mut backend = RecordingBackend::default();
+        let style = Style::default().with_custom(|backend| backend.save());
+
+        apply_style(&mut backend, &style);
+
+        assert_eq!(
+            backend.commands(),
+            &[DrawCommand::SetGlobalAlpha(1.0), DrawCommand::Save]
+        );
+    }
 }
@@ -0,0 +1,271 @@
+use crate::{Affine2, Vertex2, dimensions::Dimensions, wasm_assert};
+
+/// The smallest size of a logical pixel in CSS pixels - keeps the conversions finite while the
+/// window has no area (e.g. when it's minimized or hidden in an iframe).
+const MIN_SCALE: f32 = 0.000001;
+
+/// Decides how the logical resolution (the pixels the components, cameras and viewports work
+/// with) maps onto the window.
+///
+/// The canvas always covers the window - its backing store is scaled by the device pixel ratio,
+/// so the rendering stays sharp on HiDPI screens regardless of the policy.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Scaling, Vertex2};
+///
+/// // The battlefield is always 800x800 logical pixels - bars fill the rest of the window
+/// let scaling = Scaling::Fixed(Vertex2::new(800.0, 800.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum Scaling {
+    /// A logical pixel is a CSS pixel - the logical resolution is the size of the window
+    #[default]
+    Native,
+
+    /// A fixed logical resolution, scaled uniformly to fit the window - the unused part of the
+    /// window is left empty (letterbox or pillarbox)
+    Fixed(Vertex2<f32>),
+
+    /// A fixed logical width - the logical height follows the aspect ratio of the window
+    FitWidth(f32),
+
+    /// A fixed logical resolution, stretched to cover the window (the aspect ratio isn't kept)
+    Stretch(Vertex2<f32>),
+}
+
+/// The result of applying a [Scaling](Scaling) policy to a window - recalculated by the engine
+/// whenever the window changes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenLayout {
+    /// The size of the screen in logical pixels
+    pub logical_size: Vertex2<f32>,
+
+    /// The size of the canvas (and the window) in CSS pixels
+    pub window_size: Vertex2<f32>,
+
+    /// The size of the canvas backing store in device pixels
+    pub canvas_size: Vertex2<u32>,
+
+    pub device_pixel_ratio: f32,
+
+    /// The size of a logical pixel in CSS pixels
+    scale: Vertex2<f32>,
+
+    /// The top left corner of the logical screen in CSS pixels (the size of the bars)
+    offset: Vertex2<f32>,
+}
+
+impl Default for ScreenLayout {
+    fn default() -> Self {
+        Self::new(Scaling::Native, Vertex2::default(), 1.0)
+    }
+}
+
+impl ScreenLayout {
+    /// The `window_size` is in CSS pixels.
+    pub fn new(scaling: Scaling, window_size: Vertex2<f32>, device_pixel_ratio: f32) -> Self {
+        wasm_assert!(device_pixel_ratio.is_normal() && device_pixel_ratio.is_sign_positive());
+
+        let (logical_size, scale) = match scaling {
+            Scaling::Native => (window_size, Vertex2::new(1.0, 1.0)),
+            Scaling::Fixed(size) => {
+                let scale = (window_size.x / size.x)
+                    .min(window_size.y / size.y)
+                    .max(MIN_SCALE);
+                (size, Vertex2::new(scale, scale))
+            }
+            Scaling::FitWidth(width) => {
+                let scale = (window_size.x / width).max(MIN_SCALE);
+                (
+                    Vertex2::new(width, window_size.y / scale),
+                    Vertex2::new(scale, scale),
+                )
+            }
+            Scaling::Stretch(size) => (
+                size,
+                Vertex2::new(
+                    (window_size.x / size.x).max(MIN_SCALE),
+                    (window_size.y / size.y).max(MIN_SCALE),
+                ),
+            ),
+        };
+
+        let used = logical_size * scale;
+        Self {
+            logical_size,
+            window_size,
+            canvas_size: Vertex2::new(
+                (window_size.x * device_pixel_ratio).round() as u32,
+                (window_size.y * device_pixel_ratio).round() as u32,
+            ),
+            device_pixel_ratio,
+            scale,
+            offset: Vertex2::new(
+                (window_size.x - used.x) / 2.0,
+                (window_size.y - used.y) / 2.0,
+            ),
+        }
+    }
+
+    /// Maps logical pixels to the device pixels of the canvas backing store.
+    pub fn matrix(&self) -> Affine2 {
+        let ratio = self.device_pixel_ratio;
+        Affine2::from_scale(Vertex2::new(ratio, ratio))
+            * Affine2::from_translation(self.offset)
+            * Affine2::from_scale(self.scale)
+    }
+
+    /// Converts a point in CSS pixels relative to the canvas (e.g. the position of a mouse
+    /// event) to logical pixels.
+    pub fn to_logical(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        (point - self.offset) / self.scale
+    }
+
+    /// Whether parts of the window are not covered by the logical screen - anything drawn
+    /// outside of the logical screen has to be clipped.
+    pub fn is_letterboxed(&self) -> bool {
+        self.offset != Vertex2::default()
+    }
+
+    /// The logical screen in the device pixels of the canvas backing store.
+    pub fn canvas_area(&self) -> Dimensions {
+        transform_area(
+            &self.matrix(),
+            &Dimensions::from_top_left(
+                Vertex2::default(),
+                self.logical_size.x,
+                self.logical_size.y,
+            ),
+        )
+    }
+}
+
+/// The layout of the screen along with whether it changed in the current frame - kept by the
+/// engines and handed to the components through the [Context](crate::Context).
+#[derive(Default)]
+pub(crate) struct Screen {
+    layout: ScreenLayout,
+    resized: bool,
+}
+
+impl Screen {
+    /// Applies the layout of the window if it was reported this frame - returns whether the
+    /// layout changed.
+    pub fn update(&mut self, layout: Option<ScreenLayout>) -> bool {
+        self.resized = layout.is_some_and(|layout| layout != self.layout);
+        if let Some(layout) = layout {
+            self.layout = layout;
+        }
+        self.resized
+    }
+
+    pub fn layout(&self) -> &ScreenLayout {
+        &self.layout
+    }
+
+    pub fn resized(&self) -> bool {
+        self.resized
+    }
+}
+
+/// The bounding box of the area after it's transformed by a matrix without rotation.
+pub(crate) fn transform_area(matrix: &Affine2, area: &Dimensions) -> Dimensions {
+    let top_left = matrix.transform_point(Vertex2::new(area.left(), area.top()));
+    let bottom_right = matrix.transform_point(Vertex2::new(area.right(), area.bottom()));
+    Dimensions::from_top_left(
+        Vertex2::new(
+            top_left.x.min(bottom_right.x),
+            top_left.y.min(bottom_right.y),
+        ),
+        (bottom_right.x - top_left.x).abs(),
+        (bottom_right.y - top_left.y).abs(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Vertex2<f32> = Vertex2 {
+        x: 1000.0,
+        y: 500.0,
+    };
+
+    #[test]
+    fn native() {
+        let layout = ScreenLayout::new(Scaling::Native, WINDOW, 2.0);
+
+        assert_eq!(layout.logical_size, WINDOW);
+        assert_eq!(layout.canvas_size, Vertex2::new(2000, 1000));
+        assert_eq!(layout.matrix(), Affine2::from_scale(Vertex2::new(2.0, 2.0)));
+        assert_eq!(
+            layout.to_logical(Vertex2::new(10.0, 20.0)),
+            Vertex2::new(10.0, 20.0)
+        );
+        assert!(!layout.is_letterboxed());
+    }
+
+    #[test]
+    fn fixed() {
+        let layout = ScreenLayout::new(Scaling::Fixed(Vertex2::new(400.0, 400.0)), WINDOW, 1.0);
+
+        // Pillarboxed - scaled to the height of the window and centered horizontally
+        assert!(layout.is_letterboxed());
+        assert_eq!(layout.logical_size, Vertex2::new(400.0, 400.0));
+        assert_eq!(
+            layout.matrix(),
+            Affine2::new(1.25, 0.0, 0.0, 1.25, 250.0, 0.0)
+        );
+        assert_eq!(
+            layout.to_logical(Vertex2::new(500.0, 250.0)),
+            Vertex2::new(200.0, 200.0)
+        );
+        assert_eq!(
+            layout.canvas_area(),
+            Dimensions::from_top_left(Vertex2::new(250.0, 0.0), 500.0, 500.0)
+        );
+    }
+
+    #[test]
+    fn fit_width() {
+        let layout = ScreenLayout::new(Scaling::FitWidth(500.0), WINDOW, 1.0);
+
+        assert_eq!(layout.logical_size, Vertex2::new(500.0, 250.0));
+        assert_eq!(
+            layout.to_logical(Vertex2::new(1000.0, 500.0)),
+            Vertex2::new(500.0, 250.0)
+        );
+        assert!(!layout.is_letterboxed());
+    }
+
+    #[test]
+    fn stretch() {
+        let layout = ScreenLayout::new(Scaling::Stretch(Vertex2::new(100.0, 100.0)), WINDOW, 1.0);
+
+        assert_eq!(layout.logical_size, Vertex2::new(100.0, 100.0));
+        assert_eq!(
+            layout.to_logical(Vertex2::new(500.0, 250.0)),
+            Vertex2::new(50.0, 50.0)
+        );
+        assert!(!layout.is_letterboxed());
+    }
+
+    #[test]
+    fn empty_window() {
+        let size = Vertex2::new(100.0, 100.0);
+        for scaling in [
+            Scaling::Native,
+            Scaling::Fixed(size),
+            Scaling::FitWidth(size.x),
+            Scaling::Stretch(size),
+        ] {
+            let layout = ScreenLayout::new(scaling, Vertex2::default(), 1.0);
+            let point = layout.to_logical(Vertex2::new(10.0, 10.0));
+
+            assert!(point.x.is_finite() && point.y.is_finite(), "{scaling:?}");
+            assert!(layout.logical_size.x.is_finite() && layout.logical_size.y.is_finite());
+        }
+    }
+}
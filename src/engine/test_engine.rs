@@ -0,0 +1,195 @@
+use super::{
+    Engine,
+    asset::{AssetLoader, AssetManager, MemoryLoader},
+    camera::Camera,
+    component::Component,
+    input::{Input, Key, Mouse},
+    render_backend::{DrawCommand, RecordingBackend, RenderBackend},
+    screen::{Scaling, Screen, ScreenLayout},
+    time::{FixedTimestep, Time},
+    viewport::Viewport,
+};
+use crate::{Affine2, Vertex2, error::Result, wasm_assert};
+
+/// A headless [Engine](Engine) that doesn't need a browser - it runs the same update and render
+/// logic, but frames are stepped manually and everything is drawn to a
+/// [RecordingBackend](RecordingBackend).
+///
+/// Use it to regression-test the behavior of your components with `cargo test`.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Key, TestEngine};
+///
+/// let mut engine = TestEngine::new(Vec::default()); // Add components here
+///
+/// engine.press_key(Key::D);
+/// engine.step(1.0 / 60.0).unwrap();
+///
+/// assert!(engine.components().is_empty());
+/// assert!(!engine.commands().is_empty()); // The canvas is cleared every frame
+/// ```
+pub struct TestEngine {
+    components: Vec<Box<dyn Component>>,
+    input: Input,
+    assets: AssetManager,
+    viewports: Vec<Viewport>,
+    scaling: Scaling,
+    screen: Screen,
+    time: Time,
+    backend: RecordingBackend,
+}
+
+impl TestEngine {
+    pub fn new(mut components: Vec<Box<dyn Component>>) -> Self {
+        Engine::propagate_transforms(
+            &mut Engine::as_mut_components(&mut components),
+            &Affine2::IDENTITY,
+        );
+
+        Self {
+            components,
+            input: Input::default(),
+            assets: AssetManager::new(Box::new(MemoryLoader::default())),
+            viewports: vec![Viewport::default()],
+            scaling: Scaling::default(),
+            screen: Screen::default(),
+            time: Time::default(),
+            backend: RecordingBackend::default(),
+        }
+    }
+
+    /// Replaces the default loader (an empty [MemoryLoader](MemoryLoader)) - e.g. with a
+    /// [MemoryLoader](MemoryLoader) that contains the files your components load.
+    pub fn with_asset_loader(mut self, loader: Box<dyn AssetLoader>) -> Self {
+        self.assets = AssetManager::new(loader);
+        self
+    }
+
+    /// Replaces the asset manager - e.g. with one whose handles are already stored in the
+    /// components, since a handle only refers to an asset of the manager that handed it out.
+    pub fn with_assets(mut self, assets: AssetManager) -> Self {
+        self.assets = assets;
+        self
+    }
+
+    pub fn with_camera(self, camera: Camera) -> Self {
+        self.with_viewports(vec![Viewport::full_screen(camera)])
+    }
+
+    pub fn with_viewports(mut self, viewports: Vec<Viewport>) -> Self {
+        wasm_assert!(!viewports.is_empty());
+        self.viewports = viewports;
+        self
+    }
+
+    /// The window is the size of the [RecordingBackend](RecordingBackend) (`800x600` unless
+    /// it's resized with [resize_window](Self::resize_window)) with a device pixel ratio of `1`.
+    pub fn with_scaling(mut self, scaling: Scaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    pub fn with_fixed_timestep(mut self, fixed_timestep: FixedTimestep) -> Self {
+        self.time.set_fixed_timestep(Some(fixed_timestep));
+        self
+    }
+
+    /// Runs a single frame of the main loop with the given delta time (in seconds).
+    ///
+    /// The draw calls of the previous frame are discarded - see [commands](Self::commands).
+    ///
+    /// The frame is finished even if a component fails - the first error is returned.
+    pub fn step(&mut self, delta_time: f32) -> Result<()> {
+        self.backend.take_commands();
+        let layout = ScreenLayout::new(self.scaling, self.backend.size(), 1.0);
+        self.screen.update(Some(layout));
+        self.time.advance(delta_time);
+        Engine::frame(
+            &mut self.components,
+            &mut self.input,
+            &mut self.assets,
+            &mut self.viewports,
+            &mut self.backend,
+            &self.screen,
+            &mut self.time,
+        )
+    }
+
+    /// Calls [step](Self::step) `count` times - stops at the first failing frame.
+    pub fn step_many(&mut self, count: usize, delta_time: f32) -> Result<()> {
+        for _ in 0..count {
+            self.step(delta_time)?;
+        }
+        Ok(())
+    }
+
+    pub fn components(&self) -> &[Box<dyn Component>] {
+        &self.components
+    }
+
+    pub fn components_mut(&mut self) -> &mut [Box<dyn Component>] {
+        &mut self.components
+    }
+
+    /// The draw calls made during the last [step](Self::step).
+    pub fn commands(&self) -> &[DrawCommand] {
+        self.backend.commands()
+    }
+
+    /// Like a browser `keydown` event - the key will be "pressed" on the next frame and "down"
+    /// on the frames after that, until it's released.
+    pub fn press_key(&mut self, key: Key) {
+        self.input.keyboard.handle_key_down(key);
+    }
+
+    pub fn release_key(&mut self, key: Key) {
+        self.input.keyboard.handle_key_up(key);
+    }
+
+    pub fn press_mouse(&mut self, button: Mouse) {
+        self.input.mouse.handle_key_down(button);
+    }
+
+    pub fn release_mouse(&mut self, button: Mouse) {
+        self.input.mouse.handle_key_up(button);
+    }
+
+    pub fn move_mouse(&mut self, position: Vertex2<i32>) {
+        self.input.mouse.handle_move(position);
+    }
+
+    /// Like a browser `resize` event - the new size (in CSS pixels) is applied on the next frame.
+    pub fn resize_window(&mut self, size: Vertex2<f32>) {
+        self.backend.set_size(size);
+    }
+
+    /// Fakes a finished image load - images loaded via
+    /// [AssetManager::load_image](crate::AssetManager::load_image) never finish loading otherwise.
+    pub fn set_image_size(&mut self, path: &str, size: Vertex2<f32>) {
+        self.backend.set_image_size(path, size);
+    }
+
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// The camera of the first viewport.
+    pub fn camera(&self) -> &Camera {
+        &self.viewports[0].camera
+    }
+
+    pub fn viewports(&self) -> &[Viewport] {
+        &self.viewports
+    }
+
+    pub fn assets(&self) -> &AssetManager {
+        &self.assets
+    }
+
+    /// Use it to load the assets of the components that were created before the engine.
+    pub fn assets_mut(&mut self) -> &mut AssetManager {
+        &mut self.assets
+    }
+}
@@ -0,0 +1,319 @@
+mod timer;
+
+pub use timer::{Timer, TimerHandle, Timers};
+
+use crate::wasm_assert;
+
+/// The max delta time of the [Engine](crate::Engine) in seconds - the
+/// [TestEngine](crate::TestEngine) doesn't limit the delta times it's stepped with.
+pub const DEFAULT_MAX_DELTA_TIME: f32 = 0.25;
+
+/// Runs the [fixed_update](crate::ComponentLifecycle::fixed_update) of the components at a
+/// constant rate, independently of the framerate - so the simulation behaves the same on a 60 Hz
+/// and a 144 Hz screen and fast objects don't skip through walls when a frame takes too long.
+///
+/// The time of the frames is accumulated and spent in whole steps - `fixed_update` runs zero or
+/// more times per frame. What's left over is the [interpolation
+/// alpha](crate::Context::interpolation_alpha), which tells how far the frame is between the last
+/// two steps.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::prelude::*;
+///
+/// #[derive(Component)]
+/// pub struct Ball {
+///     previous: Vertex2<f32>,
+///     current: Vertex2<f32>,
+///     velocity: Vertex2<f32>,
+///
+///     transform: Transform,
+///     renderables: Vec<Renderable>,
+/// }
+///
+/// impl ComponentLifecycle for Ball {
+///     fn fixed_update(&mut self, ctx: &mut Context) {
+///         // The delta time is the fixed step here
+///         self.previous = self.current;
+///         self.current.x += self.velocity.x * ctx.delta_time();
+///         self.current.y += self.velocity.y * ctx.delta_time();
+///     }
+///
+///     fn update(&mut self, ctx: &mut Context) {
+///         // Render between the last two steps for smooth movement
+///         let alpha = ctx.interpolation_alpha();
+///         self.transform.position.x = self.previous.x + (self.current.x - self.previous.x) * alpha;
+///         self.transform.position.y = self.previous.y + (self.current.y - self.previous.y) * alpha;
+///     }
+/// }
+///
+/// let timestep = FixedTimestep::new(60.0).with_max_steps(4);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FixedTimestep {
+    /// In seconds
+    pub step: f32,
+
+    /// The most steps a single frame can run - when a frame takes longer than that, the time
+    /// above it is dropped (the simulation slows down instead of taking even longer to catch up)
+    pub max_steps: u32,
+}
+
+impl FixedTimestep {
+    /// The `rate` is the number of steps per second.
+    pub fn new(rate: f32) -> Self {
+        wasm_assert!(rate.is_normal() && rate.is_sign_positive());
+
+        Self {
+            step: 1.0 / rate,
+            max_steps: 5,
+        }
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+}
+
+/// The timing of the frames - available to the components via
+/// [Context::time](crate::Context::time).
+///
+/// The scaled times follow the time scale (e.g. for slow motion), while the unscaled times follow
+/// the clock - the [delta time](Self::delta_time), the fixed steps, the camera and the
+/// [timers](Timers) are all scaled.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::prelude::*;
+///
+/// fn toggle_pause(ctx: &mut Context) {
+///     if ctx.input.keyboard.is_pressed(Key::P) {
+///         let scale = if ctx.time.time_scale() == 0.0 { 1.0 } else { 0.0 };
+///         ctx.time.set_time_scale(scale);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Time {
+    /// Advanced at the start of every frame, right after the delta time is measured
+    pub timers: Timers,
+
+    delta_time: f32,
+    unscaled_delta_time: f32,
+    elapsed: f64,
+    unscaled_elapsed: f64,
+    frame_count: u64,
+
+    time_scale: f32,
+    max_delta_time: Option<f32>,
+    fixed_timestep: Option<FixedTimestep>,
+
+    /// The time that wasn't spent on fixed steps yet
+    accumulator: f32,
+    /// The number of fixed steps of the current frame
+    fixed_steps: u32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            timers: Timers::default(),
+            delta_time: 0.0,
+            unscaled_delta_time: 0.0,
+            elapsed: 0.0,
+            unscaled_elapsed: 0.0,
+            frame_count: 0,
+            time_scale: 1.0,
+            max_delta_time: None,
+            fixed_timestep: None,
+            accumulator: 0.0,
+            fixed_steps: 0,
+        }
+    }
+}
+
+impl Time {
+    /// The seconds since the last frame, multiplied by the time scale - see
+    /// [Context::delta_time](crate::Context::delta_time).
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// The seconds since the last frame, regardless of the time scale (e.g. for menus that keep
+    /// working while the game is paused) - still limited by the max delta time.
+    pub fn unscaled_delta_time(&self) -> f32 {
+        self.unscaled_delta_time
+    }
+
+    /// The sum of the scaled delta times of all frames, in seconds.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// The sum of the unscaled delta times of all frames, in seconds.
+    pub fn unscaled_elapsed(&self) -> f64 {
+        self.unscaled_elapsed
+    }
+
+    /// The number of frames so far, including the current one.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Multiplies the delta times from the next frame on - `1.0` is real time, `0.5` is slow
+    /// motion and `0.0` pauses everything that moves by the delta time.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        wasm_assert!(time_scale.is_finite() && time_scale.is_sign_positive());
+        self.time_scale = time_scale;
+    }
+
+    pub fn max_delta_time(&self) -> Option<f32> {
+        self.max_delta_time
+    }
+
+    /// Limits the (unscaled) delta time, so that a long stall (e.g. the browser tab was in the
+    /// background) doesn't make everything jump - see
+    /// [DEFAULT_MAX_DELTA_TIME](DEFAULT_MAX_DELTA_TIME).
+    pub fn set_max_delta_time(&mut self, max_delta_time: Option<f32>) {
+        self.max_delta_time = max_delta_time;
+    }
+
+    pub fn fixed_timestep(&self) -> Option<&FixedTimestep> {
+        self.fixed_timestep.as_ref()
+    }
+
+    /// See [Context::interpolation_alpha](crate::Context::interpolation_alpha).
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.fixed_timestep
+            .map_or(1.0, |fixed| self.accumulator / fixed.step)
+    }
+
+    pub(crate) fn set_fixed_timestep(&mut self, fixed_timestep: Option<FixedTimestep>) {
+        self.fixed_timestep = fixed_timestep;
+        self.accumulator = 0.0;
+    }
+
+    pub(crate) fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
+
+    /// Starts a new frame - the fixed steps that fit in the accumulated time are counted towards
+    /// the frame.
+    pub(crate) fn advance(&mut self, delta_time: f32) {
+        self.unscaled_delta_time = match self.max_delta_time {
+            Some(max_delta_time) => delta_time.min(max_delta_time),
+            None => delta_time,
+        };
+        self.delta_time = self.unscaled_delta_time * self.time_scale;
+        self.unscaled_elapsed += self.unscaled_delta_time as f64;
+        self.elapsed += self.delta_time as f64;
+        self.frame_count += 1;
+        self.timers
+            .advance(self.delta_time, self.unscaled_delta_time);
+
+        let Some(fixed) = self.fixed_timestep else {
+            return;
+        };
+
+        self.accumulator += self.delta_time;
+        self.fixed_steps = 0;
+        while self.accumulator >= fixed.step && self.fixed_steps < fixed.max_steps {
+            self.accumulator -= fixed.step;
+            self.fixed_steps += 1;
+        }
+
+        // Too far behind to catch up - the backlog is dropped
+        if self.accumulator >= fixed.step {
+            self.accumulator %= fixed.step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    fn time(fixed_timestep: FixedTimestep) -> Time {
+        let mut time = Time::default();
+        time.set_fixed_timestep(Some(fixed_timestep));
+        time
+    }
+
+    #[test]
+    fn variable_timestep() {
+        let mut time = Time::default();
+        time.advance(0.5);
+
+        assert_eq!(time.delta_time(), 0.5);
+        assert_eq!(time.fixed_steps(), 0);
+        assert_eq!(time.interpolation_alpha(), 1.0);
+    }
+
+    #[test]
+    fn time_scale() {
+        let mut time = Time::default();
+        time.advance(0.5);
+        time.set_time_scale(0.5);
+        time.advance(0.5);
+
+        assert_eq!(time.delta_time(), 0.25);
+        assert_eq!(time.unscaled_delta_time(), 0.5);
+        assert_eq!(time.elapsed(), 0.75);
+        assert_eq!(time.unscaled_elapsed(), 1.0);
+        assert_eq!(time.frame_count(), 2);
+    }
+
+    #[test]
+    fn max_delta_time() {
+        let mut time = Time::default();
+        time.set_max_delta_time(Some(DEFAULT_MAX_DELTA_TIME));
+        time.advance(10.0);
+
+        assert_eq!(time.unscaled_delta_time(), DEFAULT_MAX_DELTA_TIME);
+        assert_eq!(time.elapsed(), DEFAULT_MAX_DELTA_TIME as f64);
+    }
+
+    #[test]
+    fn paused_fixed_steps() {
+        let mut time = time(FixedTimestep::new(10.0));
+        time.set_time_scale(0.0);
+        time.advance(1.0);
+
+        assert_eq!(time.fixed_steps(), 0);
+        assert_eq!(time.interpolation_alpha(), 0.0);
+    }
+
+    #[test]
+    fn accumulate_steps() {
+        let mut time = time(FixedTimestep::new(10.0));
+
+        time.advance(0.05);
+        assert_eq!(time.fixed_steps(), 0);
+        assert!((time.interpolation_alpha() - 0.5).abs() < EPSILON);
+
+        time.advance(0.26);
+        assert_eq!(time.fixed_steps(), 3);
+        assert!((time.interpolation_alpha() - 0.1).abs() < EPSILON);
+    }
+
+    #[test]
+    fn max_steps() {
+        let mut time = time(FixedTimestep::new(10.0).with_max_steps(2));
+
+        time.advance(1.05);
+        assert_eq!(time.fixed_steps(), 2);
+        assert!((time.interpolation_alpha() - 0.5).abs() < EPSILON);
+
+        time.advance(0.1);
+        assert_eq!(time.fixed_steps(), 1);
+    }
+}
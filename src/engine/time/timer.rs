@@ -0,0 +1,254 @@
+use crate::wasm_assert;
+use std::collections::HashMap;
+
+/// Identifies a started [Timer](Timer) - returned by [Timers::start](Timers::start).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TimerHandle(u64);
+
+/// A countdown - either a one-shot timer that expires once, or a repeating timer that expires
+/// every `duration` seconds until it's cancelled.
+///
+/// Timers follow the time scale (they don't run while the game is paused) unless they're
+/// [unscaled](Self::with_unscaled).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timer {
+    duration: f32,
+    repeating: bool,
+    unscaled: bool,
+    paused: bool,
+    finished: bool,
+
+    elapsed: f32,
+    /// The expirations that weren't taken by the owner yet
+    expirations: u32,
+}
+
+impl Timer {
+    /// Expires once, after `duration` seconds.
+    pub fn once(duration: f32) -> Self {
+        wasm_assert!(duration.is_finite() && duration.is_sign_positive());
+        Self::new(duration, false)
+    }
+
+    /// Expires every `interval` seconds.
+    pub fn repeating(interval: f32) -> Self {
+        wasm_assert!(interval.is_normal() && interval.is_sign_positive());
+        Self::new(interval, true)
+    }
+
+    fn new(duration: f32, repeating: bool) -> Self {
+        Self {
+            duration,
+            repeating,
+            unscaled: false,
+            paused: false,
+            finished: false,
+            elapsed: 0.0,
+            expirations: 0,
+        }
+    }
+
+    /// Runs on the unscaled time (e.g. for menus that keep working while the game is paused).
+    pub fn with_unscaled(mut self) -> Self {
+        self.unscaled = true;
+        self
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    pub fn is_repeating(&self) -> bool {
+        self.repeating
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// The seconds until the next expiration.
+    pub fn remaining(&self) -> f32 {
+        (self.duration - self.elapsed).max(0.0)
+    }
+
+    fn advance(&mut self, delta_time: f32) {
+        if self.paused || self.finished {
+            return;
+        }
+
+        self.elapsed += delta_time;
+        if !self.repeating {
+            if self.elapsed >= self.duration {
+                self.expirations = 1;
+                self.finished = true;
+            }
+            return;
+        }
+
+        while self.elapsed >= self.duration {
+            self.elapsed -= self.duration;
+            self.expirations += 1;
+        }
+    }
+}
+
+/// The running timers - the timers are advanced at the start of every frame, so their
+/// expirations are delivered to the components that own their handles on the next `update`.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::prelude::*;
+///
+/// #[derive(Component)]
+/// pub struct Bomb {
+///     fuse: Option<TimerHandle>,
+///
+///     transform: Transform,
+///     renderables: Vec<Renderable>,
+/// }
+///
+/// impl ComponentLifecycle for Bomb {
+///     fn update(&mut self, ctx: &mut Context) {
+///         let timers = &mut ctx.time.timers;
+///         match self.fuse {
+///             None => self.fuse = Some(timers.start(Timer::once(3.0))),
+///             Some(fuse) if timers.expired(fuse) => {
+///                 // Explode
+///             }
+///             Some(_) => (),
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Timers {
+    timers: HashMap<TimerHandle, Timer>,
+    next_handle: u64,
+}
+
+impl Timers {
+    pub fn start(&mut self, timer: Timer) -> TimerHandle {
+        let handle = TimerHandle(self.next_handle);
+        self.next_handle += 1;
+        self.timers.insert(handle, timer);
+        handle
+    }
+
+    /// Stops and removes the timer - its pending expirations are dropped. Returns `false` if
+    /// the timer doesn't exist (anymore).
+    pub fn cancel(&mut self, handle: TimerHandle) -> bool {
+        self.timers.remove(&handle).is_some()
+    }
+
+    pub fn pause(&mut self, handle: TimerHandle) {
+        if let Some(timer) = self.timers.get_mut(&handle) {
+            timer.paused = true;
+        }
+    }
+
+    pub fn resume(&mut self, handle: TimerHandle) {
+        if let Some(timer) = self.timers.get_mut(&handle) {
+            timer.paused = false;
+        }
+    }
+
+    /// `None` once the timer is cancelled, or once the expiration of a one-shot timer is taken.
+    pub fn get(&self, handle: TimerHandle) -> Option<&Timer> {
+        self.timers.get(&handle)
+    }
+
+    /// Takes the expirations of the timer since the last call - a repeating timer can expire
+    /// more than once in a single frame (e.g. when its interval is shorter than the frame).
+    ///
+    /// The one-shot timers are removed once their expiration is taken.
+    pub fn take_expirations(&mut self, handle: TimerHandle) -> u32 {
+        let Some(timer) = self.timers.get_mut(&handle) else {
+            return 0;
+        };
+
+        let expirations = std::mem::take(&mut timer.expirations);
+        if timer.finished && expirations > 0 {
+            self.timers.remove(&handle);
+        }
+        expirations
+    }
+
+    /// Like [take_expirations](Self::take_expirations), but only tells whether the timer expired.
+    pub fn expired(&mut self, handle: TimerHandle) -> bool {
+        self.take_expirations(handle) > 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    pub(crate) fn advance(&mut self, delta_time: f32, unscaled_delta_time: f32) {
+        for timer in self.timers.values_mut() {
+            if timer.unscaled {
+                timer.advance(unscaled_delta_time);
+            } else {
+                timer.advance(delta_time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn once() {
+        let mut timers = Timers::default();
+        let handle = timers.start(Timer::once(1.0));
+
+        timers.advance(0.5, 0.5);
+        assert!(!timers.expired(handle));
+        assert_eq!(timers.get(handle).map(Timer::remaining), Some(0.5));
+
+        timers.advance(0.5, 0.5);
+        assert!(timers.expired(handle));
+        assert!(timers.get(handle).is_none());
+        assert!(!timers.expired(handle));
+    }
+
+    #[test]
+    fn repeating() {
+        let mut timers = Timers::default();
+        let handle = timers.start(Timer::repeating(0.25));
+
+        timers.advance(0.6, 0.6);
+        assert_eq!(timers.take_expirations(handle), 2);
+        assert_eq!(timers.take_expirations(handle), 0);
+
+        timers.advance(0.15, 0.15);
+        assert!(timers.expired(handle));
+        assert!(timers.cancel(handle));
+        assert!(!timers.cancel(handle));
+    }
+
+    #[test]
+    fn pause() {
+        let mut timers = Timers::default();
+        let scaled = timers.start(Timer::once(1.0));
+        let unscaled = timers.start(Timer::once(1.0).with_unscaled());
+        let paused = timers.start(Timer::once(1.0));
+        timers.pause(paused);
+
+        // The global time scale is 0
+        timers.advance(0.0, 1.0);
+        assert!(!timers.expired(scaled));
+        assert!(timers.expired(unscaled));
+
+        timers.resume(paused);
+        timers.advance(1.0, 1.0);
+        assert!(timers.expired(scaled));
+        assert!(timers.expired(paused));
+        assert!(timers.is_empty());
+    }
+}
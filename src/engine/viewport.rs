@@ -0,0 +1,222 @@
+use super::camera::Camera;
+use crate::{Vertex2, dimensions::Dimensions, wasm_assert};
+
+/// A part of the canvas that the scene is rendered into through its own [Camera](Camera) - the
+/// scene is rendered once per viewport and clipped to its area (e.g. for local multiplayer split
+/// screens).
+///
+/// Screen pixels are the logical pixels of the [Scaling](super::screen::Scaling) policy.
+///
+/// The camera's screen is the viewport - the screen layers of the camera are drawn relative to
+/// the top left corner of the viewport and [Camera::screen_size](Camera::screen_size) is the size
+/// of the viewport.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::prelude::*;
+///
+/// const HUD: Layer = Layer(-1);
+///
+/// // Side by side, each player has their own camera and HUD
+/// let mut viewports = Viewport::split(2, 1);
+/// for viewport in &mut viewports {
+///     viewport.camera = Camera::default().with_screen_layer(HUD);
+/// }
+///
+/// // The health bar of the second player is only drawn in the second viewport
+/// let health_bar = ViewportMask::only(1);
+/// assert!(!health_bar.contains(0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Viewport {
+    /// The part of the canvas covered by the viewport - in fractions of the canvas size (e.g. a
+    /// width of `0.5` is half of the canvas), so the viewport follows the canvas when it resizes
+    pub area: Dimensions,
+
+    pub camera: Camera,
+
+    /// The area in screen pixels - kept up to date by the engine
+    screen_area: Dimensions,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::full_screen(Camera::default())
+    }
+}
+
+impl Viewport {
+    pub fn new(area: Dimensions, camera: Camera) -> Self {
+        Self {
+            area,
+            camera,
+            screen_area: Dimensions::new(Vertex2::default(), 0.0, 0.0),
+        }
+    }
+
+    /// Covers the entire canvas.
+    pub fn full_screen(camera: Camera) -> Self {
+        Self::new(
+            Dimensions::from_top_left(Vertex2::default(), 1.0, 1.0),
+            camera,
+        )
+    }
+
+    /// A grid of viewports with default cameras that covers the entire canvas - ordered left to
+    /// right, top to bottom (e.g. `split(2, 1)` for two players side by side and `split(2, 2)`
+    /// for four players).
+    pub fn split(columns: usize, rows: usize) -> Vec<Viewport> {
+        wasm_assert!(columns > 0 && rows > 0);
+
+        let width = 1.0 / columns as f32;
+        let height = 1.0 / rows as f32;
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let top_left = Vertex2::new(column as f32 * width, row as f32 * height);
+                Viewport::new(
+                    Dimensions::from_top_left(top_left, width, height),
+                    Camera::default(),
+                )
+            })
+            .collect()
+    }
+
+    /// The area in screen pixels.
+    pub fn screen_area(&self) -> Dimensions {
+        self.screen_area
+    }
+
+    /// The top left corner of the viewport in screen pixels.
+    pub fn offset(&self) -> Vertex2<f32> {
+        Vertex2::new(self.screen_area.left(), self.screen_area.top())
+    }
+
+    /// Whether the point in screen pixels (e.g. the mouse position) is within the viewport.
+    pub fn contains(&self, point: Vertex2<f32>) -> bool {
+        self.screen_area.contains(point)
+    }
+
+    /// Converts a point in screen pixels to world coordinates as seen through the camera.
+    pub fn screen_to_world(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        self.camera.screen_to_world(point - self.offset())
+    }
+
+    /// Converts a point in world coordinates to canvas pixels as seen through the camera.
+    pub fn world_to_screen(&self, point: Vertex2<f32>) -> Vertex2<f32> {
+        self.camera.world_to_screen(point) + self.offset()
+    }
+
+    /// Whether the viewport covers the entire canvas, so there's nothing to clip.
+    pub(crate) fn is_full_screen(&self) -> bool {
+        self.area == Dimensions::from_top_left(Vertex2::default(), 1.0, 1.0)
+    }
+
+    pub(crate) fn set_canvas_size(&mut self, canvas_size: Vertex2<f32>) {
+        self.screen_area = Dimensions::from_top_left(
+            Vertex2::new(
+                self.area.left() * canvas_size.x,
+                self.area.top() * canvas_size.y,
+            ),
+            self.area.width() * canvas_size.x,
+            self.area.height() * canvas_size.y,
+        );
+        self.camera.set_screen_size(Vertex2::new(
+            self.screen_area.width(),
+            self.screen_area.height(),
+        ));
+    }
+}
+
+/// The viewports a [Renderable](crate::Renderable) is drawn in - by viewport index, so up to 32
+/// viewports can be told apart. Renderables are drawn in all viewports by default.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ViewportMask(pub u32);
+
+impl Default for ViewportMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl ViewportMask {
+    pub const ALL: ViewportMask = ViewportMask(u32::MAX);
+    pub const NONE: ViewportMask = ViewportMask(0);
+
+    pub fn only(viewport: usize) -> Self {
+        Self::NONE.with(viewport)
+    }
+
+    pub fn with(self, viewport: usize) -> Self {
+        wasm_assert!(viewport < u32::BITS as usize);
+        Self(self.0 | 1 << viewport)
+    }
+
+    pub fn without(self, viewport: usize) -> Self {
+        wasm_assert!(viewport < u32::BITS as usize);
+        Self(self.0 & !(1 << viewport))
+    }
+
+    pub fn contains(self, viewport: usize) -> bool {
+        viewport < u32::BITS as usize && self.0 & 1 << viewport != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let mut viewports = Viewport::split(2, 2);
+        for viewport in &mut viewports {
+            viewport.set_canvas_size(Vertex2::new(800.0, 600.0));
+        }
+
+        let offsets: Vec<Vertex2<f32>> = viewports.iter().map(Viewport::offset).collect();
+        assert_eq!(
+            offsets,
+            [
+                Vertex2::new(0.0, 0.0),
+                Vertex2::new(400.0, 0.0),
+                Vertex2::new(0.0, 300.0),
+                Vertex2::new(400.0, 300.0),
+            ]
+        );
+        assert_eq!(
+            viewports[3].camera.screen_size(),
+            Vertex2::new(400.0, 300.0)
+        );
+        assert!(!viewports[3].is_full_screen());
+        assert!(Viewport::default().is_full_screen());
+    }
+
+    #[test]
+    fn screen_to_world() {
+        let mut viewport = Viewport::split(2, 1).remove(1);
+        viewport.camera.position = Vertex2::new(1000.0, 0.0);
+        viewport.set_canvas_size(Vertex2::new(800.0, 600.0));
+
+        assert!(viewport.contains(Vertex2::new(500.0, 100.0)));
+        assert!(!viewport.contains(Vertex2::new(300.0, 100.0)));
+        assert_eq!(
+            viewport.screen_to_world(Vertex2::new(500.0, 100.0)),
+            Vertex2::new(1100.0, 100.0)
+        );
+        assert_eq!(
+            viewport.world_to_screen(Vertex2::new(1100.0, 100.0)),
+            Vertex2::new(500.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn mask() {
+        let mask = ViewportMask::only(1).with(3);
+
+        assert!(mask.contains(1) && mask.contains(3));
+        assert!(!mask.contains(0) && !mask.contains(40));
+        assert!(!mask.without(3).contains(3));
+        assert!(ViewportMask::ALL.contains(31));
+    }
+}
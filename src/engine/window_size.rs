@@ -0,0 +1,63 @@
+use super::input::event_listener::{self, EventHandler, EventListener};
+use crate::{Vertex2, error::Result};
+use log::warn;
+use std::{cell::RefCell, rc::Rc};
+use web_sys::{Event, Window};
+
+/// The size of the window in CSS pixels and its device pixel ratio.
+pub type WindowMetrics = (Vertex2<f32>, f32);
+
+/// Caches the size of the window - it's only queried when the browser reports a `resize`, so the
+/// main loop doesn't cross the WASM boundary every frame.
+///
+/// > **Note:** browser zoom fires a `resize`, but moving the window to a screen with a different
+/// > device pixel ratio doesn't in every browser - the canvas stays at the old ratio (slightly
+/// > blurry or oversized) until the window is resized.
+#[derive(Default)]
+pub struct WindowSize {
+    /// The metrics that haven't been applied to the canvas yet
+    pending: Rc<RefCell<Option<WindowMetrics>>>,
+}
+
+impl WindowSize {
+    /// Queries the window and attaches the `resize` handler - it's detached when the returned
+    /// listener is dropped.
+    pub fn init(&self, window: &Window) -> Result<EventListener> {
+        *self.pending.borrow_mut() = Some(query(window)?);
+
+        let listener_pending = self.pending.clone();
+        let listener_window = window.clone();
+        event_listener::attach(
+            window,
+            "resize",
+            EventHandler::new(move |_: Event| match query(&listener_window) {
+                Ok(metrics) => *listener_pending.borrow_mut() = Some(metrics),
+                Err(err) => warn!("{}", err),
+            }),
+        )
+    }
+
+    /// The metrics of the window if they were reported since the last call.
+    pub fn take(&self) -> Option<WindowMetrics> {
+        self.pending.borrow_mut().take()
+    }
+}
+
+fn query(window: &Window) -> Result<WindowMetrics> {
+    let width = window
+        .inner_width()
+        .map_err(|_| "Failed to get window's inner width")?
+        .as_f64()
+        .ok_or("Failed to convert window's inner width to f64")?;
+
+    let height = window
+        .inner_height()
+        .map_err(|_| "Failed to get window's inner height")?
+        .as_f64()
+        .ok_or("Failed to convert window's inner height to f64")?;
+
+    Ok((
+        Vertex2::new(width as f32, height as f32),
+        window.device_pixel_ratio() as f32,
+    ))
+}
@@ -1,20 +1,47 @@
 #![doc = include_str!("../README.md")]
 
+// The `Component` derive refers to the crate by name, so it works within the crate as well
+extern crate self as twors;
+
+mod affine2;
 mod engine;
 mod error;
+mod path;
 mod vertex2;
 mod wasm_assert;
 
+#[cfg(test)]
+mod test_utils;
+
+pub mod animation;
 pub mod dimensions;
 pub mod prelude;
 pub mod shape_factory;
+pub mod tilemap;
 
+pub use affine2::Affine2;
 pub use engine::{
-    Context, Engine,
+    Context, Engine, EngineHandle,
+    asset::{
+        Asset, AssetLoader, AssetManager, FetchLoader, FileLoader, Handle, Image, ImageHandle,
+        ImageLoader, LoadResult, LoadState, MemoryLoader,
+    },
+    camera::Camera,
     component::{
-        Component, ComponentGetter, ComponentLifecycle, Layer, Renderable, transform::Transform,
+        Component, ComponentGetter, ComponentLifecycle, Layer, Renderable, Shape,
+        sprite::Sprite,
+        style::{CustomStyle, FillRule, LineCap, LineJoin, Style},
+        text::{Font, FontWeight, Text, TextAlign, TextBaseline, TextMeasurer},
+        transform::Transform,
     },
+    error_policy::{ErrorAction, ErrorHandler, ErrorPolicy},
     input::{Key, Mouse},
+    render_backend::{DrawCommand, RecordingBackend, RenderBackend},
+    screen::{Scaling, ScreenLayout},
+    test_engine::TestEngine,
+    time::{DEFAULT_MAX_DELTA_TIME, FixedTimestep, Time, Timer, TimerHandle, Timers},
+    viewport::{Viewport, ViewportMask},
 };
 pub use error::{Error, Result};
+pub use path::{Contour, Path, Segment};
 pub use vertex2::Vertex2;
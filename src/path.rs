@@ -0,0 +1,481 @@
+//! Describes the outline of a [Renderable](crate::Renderable) as a list of sub-paths (contours).
+//!
+//! The common case - a single closed polygon - is created via [Path::polygon] or by converting a
+//! list of vertices (e.g. from the `shape_factory`) via `into()`.
+//!
+//! Contours can contain curves (see [Segment](Segment)) - those are drawn natively by the canvas
+//! and can be flattened into vertices (e.g. for hit-testing and collision) via
+//! [Path::flatten].
+
+use crate::Vertex2;
+
+/// A single piece of a [Contour](Contour) - it starts where the previous segment ended (or at the
+/// start of the contour).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Segment {
+    Line {
+        to: Vertex2<f32>,
+    },
+
+    /// A quadratic Bézier curve.
+    Quadratic {
+        control: Vertex2<f32>,
+        to: Vertex2<f32>,
+    },
+
+    /// A cubic Bézier curve.
+    Cubic {
+        control_1: Vertex2<f32>,
+        control_2: Vertex2<f32>,
+        to: Vertex2<f32>,
+    },
+
+    /// Like the canvas' `arcTo` - a circular arc with the given `radius` that touches both the
+    /// line towards the `corner` and the line from the `corner` to `to` (i.e. a rounded corner).
+    ///
+    /// > **Note:** the segment ends where the arc touches the second line - not at `to`.
+    ArcTo {
+        corner: Vertex2<f32>,
+        to: Vertex2<f32>,
+        radius: f32,
+    },
+}
+
+impl Segment {
+    /// The point the next segment starts from, given the point this segment starts `from`.
+    pub fn end(&self, from: Vertex2<f32>) -> Vertex2<f32> {
+        match *self {
+            Segment::Line { to } | Segment::Quadratic { to, .. } | Segment::Cubic { to, .. } => to,
+            Segment::ArcTo { corner, to, radius } => {
+                match ArcGeometry::new(from, corner, to, radius) {
+                    Some(arc) => arc.end,
+                    None => corner,
+                }
+            }
+        }
+    }
+
+    /// Approximates the segment with straight lines - the start point (`from`) is not included.
+    ///
+    /// Curves are split into `segments` lines.
+    pub fn flatten_into(
+        &self,
+        from: Vertex2<f32>,
+        segments: usize,
+        vertices: &mut Vec<Vertex2<f32>>,
+    ) {
+        let segments = segments.max(1);
+        let progress = (1..=segments).map(|segment| segment as f32 / segments as f32);
+
+        match *self {
+            Segment::Line { to } => vertices.push(to),
+            Segment::Quadratic { control, to } => {
+                vertices.extend(progress.map(|t| {
+                    let inverse = 1.0 - t;
+                    weighted(from, inverse * inverse)
+                        + weighted(control, 2.0 * inverse * t)
+                        + weighted(to, t * t)
+                }));
+            }
+            Segment::Cubic {
+                control_1,
+                control_2,
+                to,
+            } => {
+                vertices.extend(progress.map(|t| {
+                    let inverse = 1.0 - t;
+                    weighted(from, inverse * inverse * inverse)
+                        + weighted(control_1, 3.0 * inverse * inverse * t)
+                        + weighted(control_2, 3.0 * inverse * t * t)
+                        + weighted(to, t * t * t)
+                }));
+            }
+            Segment::ArcTo { corner, to, radius } => {
+                match ArcGeometry::new(from, corner, to, radius) {
+                    Some(arc) => {
+                        vertices.push(arc.start);
+                        vertices.extend(progress.take(segments - 1).map(|t| arc.point(t)));
+                        vertices.push(arc.end);
+                    }
+                    None => vertices.push(corner),
+                }
+            }
+        }
+    }
+}
+
+/// The circle that an `ArcTo` segment is a part of.
+struct ArcGeometry {
+    /// Where the arc touches the line towards the corner.
+    start: Vertex2<f32>,
+    /// Where the arc touches the line from the corner.
+    end: Vertex2<f32>,
+    center: Vertex2<f32>,
+    radius: f32,
+    start_angle: f32,
+    sweep: f32,
+}
+
+impl ArcGeometry {
+    /// Returns `None` when the arc degenerates into a line to the corner (e.g. a radius of `0` or
+    /// points on a straight line) - the same as the canvas does.
+    fn new(
+        from: Vertex2<f32>,
+        corner: Vertex2<f32>,
+        to: Vertex2<f32>,
+        radius: f32,
+    ) -> Option<Self> {
+        let incoming = (from - corner).normalize()?;
+        let outgoing = (to - corner).normalize()?;
+
+        let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+        if radius <= 0.0 || cross.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let half_angle = incoming.dot(outgoing).clamp(-1.0, 1.0).acos() / 2.0;
+        let tangent_distance = radius / half_angle.tan();
+        let bisector = (incoming + outgoing).normalize()?;
+        let center = corner + weighted(bisector, radius / half_angle.sin());
+
+        let start = corner + weighted(incoming, tangent_distance);
+        let end = corner + weighted(outgoing, tangent_distance);
+        let start_angle = angle_of(start - center);
+        let mut sweep = angle_of(end - center) - start_angle;
+        // The arc is always the short way around
+        if sweep > std::f32::consts::PI {
+            sweep -= std::f32::consts::TAU;
+        } else if sweep < -std::f32::consts::PI {
+            sweep += std::f32::consts::TAU;
+        }
+
+        Some(Self {
+            start,
+            end,
+            center,
+            radius,
+            start_angle,
+            sweep,
+        })
+    }
+
+    fn point(&self, progress: f32) -> Vertex2<f32> {
+        let (sin, cos) = (self.start_angle + self.sweep * progress).sin_cos();
+        self.center + Vertex2::new(cos * self.radius, sin * self.radius)
+    }
+}
+
+fn weighted(vertex: Vertex2<f32>, weight: f32) -> Vertex2<f32> {
+    Vertex2::new(vertex.x * weight, vertex.y * weight)
+}
+
+fn angle_of(vector: Vertex2<f32>) -> f32 {
+    vector.y.atan2(vector.x)
+}
+
+/// A single sub-path - a start point followed by [Segment](Segment)s.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Contour, Vertex2};
+///
+/// // A drop shape
+/// let drop = Contour::new(Vertex2::new(0.0, -20.0))
+///     .cubic_to(Vertex2::new(20.0, 0.0), Vertex2::new(10.0, 20.0), Vertex2::new(0.0, 20.0))
+///     .cubic_to(Vertex2::new(-10.0, 20.0), Vertex2::new(-20.0, 0.0), Vertex2::new(0.0, -20.0))
+///     .close();
+/// ```
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Contour {
+    pub start: Vertex2<f32>,
+    pub segments: Vec<Segment>,
+
+    /// A closed contour connects the end of the last segment back to the start.
+    pub closed: bool,
+}
+
+impl Contour {
+    /// An open contour without any segments - add them via the builder methods.
+    pub fn new(start: Vertex2<f32>) -> Self {
+        Self {
+            start,
+            segments: Vec::default(),
+            closed: false,
+        }
+    }
+
+    /// A polygon - the vertices are connected by straight lines.
+    pub fn closed(vertices: Vec<Vertex2<f32>>) -> Self {
+        Contour::open(vertices).close()
+    }
+
+    /// A polyline - the vertices are connected by straight lines.
+    pub fn open(vertices: Vec<Vertex2<f32>>) -> Self {
+        let mut vertices = vertices.into_iter();
+        let start = vertices.next().unwrap_or_default();
+        Self {
+            start,
+            segments: vertices.map(|to| Segment::Line { to }).collect(),
+            closed: false,
+        }
+    }
+
+    pub fn line_to(mut self, to: Vertex2<f32>) -> Self {
+        self.segments.push(Segment::Line { to });
+        self
+    }
+
+    pub fn quadratic_to(mut self, control: Vertex2<f32>, to: Vertex2<f32>) -> Self {
+        self.segments.push(Segment::Quadratic { control, to });
+        self
+    }
+
+    pub fn cubic_to(
+        mut self,
+        control_1: Vertex2<f32>,
+        control_2: Vertex2<f32>,
+        to: Vertex2<f32>,
+    ) -> Self {
+        self.segments.push(Segment::Cubic {
+            control_1,
+            control_2,
+            to,
+        });
+        self
+    }
+
+    /// See [Segment::ArcTo].
+    pub fn arc_to(mut self, corner: Vertex2<f32>, to: Vertex2<f32>, radius: f32) -> Self {
+        self.segments.push(Segment::ArcTo { corner, to, radius });
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    /// Approximates the contour with straight lines - every curve is split into `segments`
+    /// lines.
+    pub fn flatten(&self, segments: usize) -> Vec<Vertex2<f32>> {
+        let mut vertices = vec![self.start];
+        let mut current = self.start;
+        for segment in &self.segments {
+            segment.flatten_into(current, segments, &mut vertices);
+            current = segment.end(current);
+        }
+        vertices
+    }
+
+    /// Positive for clockwise contours, negative for counter-clockwise ones (on the canvas, where
+    /// the `y` axis points down).
+    fn signed_area(&self) -> f32 {
+        const SEGMENTS: usize = 8;
+
+        let vertices = self.flatten(SEGMENTS);
+        let mut area = 0.0;
+        for (index, curr) in vertices.iter().enumerate() {
+            let next = vertices[(index + 1) % vertices.len()];
+            area += curr.x * next.y - next.x * curr.y;
+        }
+        area / 2.0
+    }
+}
+
+/// A shape made of one or more [Contour](Contour)s.
+///
+/// Whether a contour adds to or cuts from the filled area is decided by the
+/// [FillRule](crate::FillRule) of the [Style](crate::Style) - holes added via
+/// [with_hole](Self::with_hole) work with both fill rules.
+///
+/// ## Example
+///
+/// ```rust
+/// use twors::{Path, Vertex2, shape_factory};
+///
+/// // A square frame
+/// let frame = Path::polygon(shape_factory::square(40.0)).with_hole(shape_factory::square(30.0));
+///
+/// // A laser beam
+/// let beam = Path::polyline(vec![Vertex2::new(0.0, 0.0), Vertex2::new(500.0, 0.0)]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Path {
+    pub contours: Vec<Contour>,
+}
+
+impl Path {
+    /// A single closed contour.
+    pub fn polygon(vertices: Vec<Vertex2<f32>>) -> Self {
+        Path::default().with_polygon(vertices)
+    }
+
+    /// A single open contour - meant to be stroked (e.g. lines, trails).
+    pub fn polyline(vertices: Vec<Vertex2<f32>>) -> Self {
+        Path::default().with_polyline(vertices)
+    }
+
+    pub fn with_polygon(self, vertices: Vec<Vertex2<f32>>) -> Self {
+        self.with_contour(Contour::closed(vertices))
+    }
+
+    pub fn with_polyline(self, vertices: Vec<Vertex2<f32>>) -> Self {
+        self.with_contour(Contour::open(vertices))
+    }
+
+    /// Adds any contour - use this for contours with curves.
+    pub fn with_contour(mut self, contour: Contour) -> Self {
+        self.contours.push(contour);
+        self
+    }
+
+    /// Adds a closed contour that's wound in the opposite direction of the first contour - this
+    /// cuts a hole with both the `NonZero` and the `EvenOdd` fill rules.
+    pub fn with_hole(mut self, mut vertices: Vec<Vertex2<f32>>) -> Self {
+        if let Some(outer) = self.contours.first() {
+            let outer_area = outer.signed_area();
+            let hole_area = Contour::closed(vertices.clone()).signed_area();
+            if outer_area.signum() == hole_area.signum() {
+                vertices.reverse();
+            }
+        }
+
+        self.contours.push(Contour::closed(vertices));
+        self
+    }
+
+    /// Approximates every contour with straight lines (see [Contour::flatten]).
+    pub fn flatten(&self, segments: usize) -> Vec<Vec<Vertex2<f32>>> {
+        self.contours
+            .iter()
+            .map(|contour| contour.flatten(segments))
+            .collect()
+    }
+}
+
+impl From<Vec<Vertex2<f32>>> for Path {
+    /// A single closed contour - the same as [Path::polygon].
+    fn from(vertices: Vec<Vertex2<f32>>) -> Self {
+        Path::polygon(vertices)
+    }
+}
+
+impl From<Contour> for Path {
+    fn from(contour: Contour) -> Self {
+        Path::default().with_contour(contour)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_factory;
+    use crate::test_utils::assert_near;
+
+    #[test]
+    fn from_vertices() {
+        let path = Path::from(shape_factory::square(10.0));
+
+        assert_eq!(path.contours.len(), 1);
+        assert!(path.contours[0].closed);
+        assert_eq!(path.flatten(1), vec![shape_factory::square(10.0)]);
+    }
+
+    #[test]
+    fn polyline() {
+        let vertices = vec![Vertex2::new(0.0, 0.0), Vertex2::new(10.0, 0.0)];
+        let path = Path::polyline(vertices.clone()).with_polyline(vertices);
+
+        assert_eq!(path.contours.len(), 2);
+        assert!(path.contours.iter().all(|contour| !contour.closed));
+    }
+
+    #[test]
+    fn signed_area() {
+        let clockwise = Contour::closed(shape_factory::square(10.0));
+        assert_eq!(clockwise.signed_area(), 100.0);
+
+        let mut vertices = shape_factory::square(10.0);
+        vertices.reverse();
+        assert_eq!(Contour::closed(vertices).signed_area(), -100.0);
+    }
+
+    #[test]
+    fn hole_winding_is_reversed() {
+        let path = Path::polygon(shape_factory::square(10.0)).with_hole(shape_factory::square(5.0));
+
+        let outer = path.contours[0].signed_area();
+        let hole = path.contours[1].signed_area();
+        assert!(outer > 0.0);
+        assert!(hole < 0.0);
+    }
+
+    #[test]
+    fn hole_winding_is_kept() {
+        let mut hole = shape_factory::square(5.0);
+        hole.reverse();
+        let path = Path::polygon(shape_factory::square(10.0)).with_hole(hole.clone());
+
+        assert_eq!(path.contours[1].flatten(1), hole);
+    }
+
+    #[test]
+    fn flatten_quadratic() {
+        let contour = Contour::new(Vertex2::new(0.0, 0.0))
+            .quadratic_to(Vertex2::new(10.0, 10.0), Vertex2::new(20.0, 0.0));
+
+        let vertices = contour.flatten(2);
+        assert_eq!(vertices.len(), 3);
+        assert_near(vertices[1], Vertex2::new(10.0, 5.0));
+        assert_near(vertices[2], Vertex2::new(20.0, 0.0));
+    }
+
+    #[test]
+    fn flatten_cubic() {
+        let contour = Contour::new(Vertex2::new(0.0, 0.0)).cubic_to(
+            Vertex2::new(0.0, 10.0),
+            Vertex2::new(30.0, 10.0),
+            Vertex2::new(30.0, 0.0),
+        );
+
+        let vertices = contour.flatten(2);
+        assert_eq!(vertices.len(), 3);
+        assert_near(vertices[1], Vertex2::new(15.0, 7.5));
+        assert_near(vertices[2], Vertex2::new(30.0, 0.0));
+    }
+
+    #[test]
+    fn flatten_arc_to() {
+        // A rounded top-right corner of a square
+        let contour = Contour::new(Vertex2::new(0.0, 0.0)).arc_to(
+            Vertex2::new(20.0, 0.0),
+            Vertex2::new(20.0, 20.0),
+            10.0,
+        );
+
+        let vertices = contour.flatten(2);
+        assert_eq!(vertices.len(), 4);
+        assert_near(vertices[1], Vertex2::new(10.0, 0.0));
+        let diagonal = 10.0 + 10.0 * std::f32::consts::FRAC_PI_4.cos();
+        assert_near(vertices[2], Vertex2::new(diagonal, 20.0 - diagonal));
+        assert_near(vertices[3], Vertex2::new(20.0, 10.0));
+
+        let end = contour.segments[0].end(contour.start);
+        assert_near(end, Vertex2::new(20.0, 10.0));
+    }
+
+    #[test]
+    fn flatten_degenerate_arc_to() {
+        let contour = Contour::new(Vertex2::new(0.0, 0.0)).arc_to(
+            Vertex2::new(10.0, 0.0),
+            Vertex2::new(20.0, 0.0),
+            5.0,
+        );
+
+        assert_eq!(
+            contour.flatten(4),
+            vec![Vertex2::new(0.0, 0.0), Vertex2::new(10.0, 0.0)]
+        );
+    }
+}
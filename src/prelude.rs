@@ -1,6 +1,7 @@
 pub use crate::{
-    ComponentGetter, ComponentLifecycle, Context, Engine, Key, Layer, Mouse, Renderable, Transform,
-    Vertex2,
+    Affine2, Camera, ComponentGetter, ComponentLifecycle, Context, Engine, EngineHandle,
+    FixedTimestep, Key, Layer, Mouse, Path, RenderBackend, Renderable, Scaling, Sprite, Style,
+    Text, Time, Timer, TimerHandle, Transform, Vertex2, Viewport, ViewportMask,
 };
 pub use twors_derive::Component;
 pub use web_sys::CanvasRenderingContext2d;
@@ -2,8 +2,18 @@
 //!
 //! > **Note:** all of the shapes created via this module have a center of (0.0, 0.0) - this means
 //! > that the vertices of the left side of the shape have a negative `x` vertex value.
+//!
+//! Curved shapes are approximated by straight lines - the `segments` argument controls how many
+//! (more segments means a smoother curve, but more vertices to render).
+//!
+//! Angles are in radians - `0.0` points to the right (positive `x`) and positive angles go
+//! clockwise since the canvas' `y` axis points down.
+//!
+//! The arguments are always in the same order - the size (width and height, or the radii)
+//! first, then the angles, then the number of segments, sides or points.
 
-use crate::Vertex2;
+use crate::{Vertex2, wasm_assert};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
 pub fn square(size: f32) -> Vec<Vertex2<f32>> {
     rectangle(size, size)
@@ -32,9 +42,141 @@ pub fn rectangle(width: f32, height: f32) -> Vec<Vertex2<f32>> {
     ]
 }
 
+pub fn circle(radius: f32, segments: usize) -> Vec<Vertex2<f32>> {
+    ellipse(radius * 2.0, radius * 2.0, segments)
+}
+
+pub fn ellipse(width: f32, height: f32, segments: usize) -> Vec<Vertex2<f32>> {
+    wasm_assert!(segments > 2);
+
+    let radius = Vertex2::new(width / 2.0, height / 2.0);
+    (0..segments)
+        .map(|segment| {
+            let angle = TAU * segment as f32 / segments as f32;
+            point_on_ellipse(radius, angle)
+        })
+        .collect()
+}
+
+/// The outline of a part of a circle with a center of (0.0, 0.0) - this is an open shape (use
+/// it with `Path::polyline`).
+pub fn arc(radius: f32, start_angle: f32, end_angle: f32, segments: usize) -> Vec<Vertex2<f32>> {
+    wasm_assert!(segments > 0);
+
+    let radius = Vertex2::new(radius, radius);
+    (0..=segments)
+        .map(|segment| {
+            let progress = segment as f32 / segments as f32;
+            let angle = start_angle + (end_angle - start_angle) * progress;
+            point_on_ellipse(radius, angle)
+        })
+        .collect()
+}
+
+/// Like [arc], but closed through the center of the circle (a pie slice).
+pub fn pie(radius: f32, start_angle: f32, end_angle: f32, segments: usize) -> Vec<Vertex2<f32>> {
+    let mut vertices = vec![Vertex2::default()];
+    vertices.extend(arc(radius, start_angle, end_angle, segments));
+    vertices
+}
+
+/// A rectangle with corners rounded by `radius` - each corner is made of `segments` lines.
+///
+/// The radius is limited to half of the shorter side.
+pub fn rounded_rectangle(
+    width: f32,
+    height: f32,
+    radius: f32,
+    segments: usize,
+) -> Vec<Vertex2<f32>> {
+    let half_width = width / 2.0;
+    let half_height = height / 2.0;
+    let radius = radius.min(half_width).min(half_height);
+
+    // Clockwise, starting from the top-left corner
+    let corners = [
+        (
+            Vertex2::new(-half_width + radius, -half_height + radius),
+            PI,
+        ),
+        (
+            Vertex2::new(half_width - radius, -half_height + radius),
+            PI + FRAC_PI_2,
+        ),
+        (Vertex2::new(half_width - radius, half_height - radius), 0.0),
+        (
+            Vertex2::new(-half_width + radius, half_height - radius),
+            FRAC_PI_2,
+        ),
+    ];
+
+    let mut vertices = Vec::default();
+    for (center, start_angle) in corners {
+        let corner = arc(radius, start_angle, start_angle + FRAC_PI_2, segments);
+        vertices.extend(corner.into_iter().map(|vertex| vertex + center));
+    }
+
+    // Sides with a length of 0 (e.g. in a capsule) leave duplicate vertices behind
+    vertices.dedup_by(|curr, prev| is_same_vertex(*curr, *prev));
+    if vertices.len() > 1 && is_same_vertex(vertices[0], vertices[vertices.len() - 1]) {
+        vertices.pop();
+    }
+
+    vertices
+}
+
+/// A rectangle with fully rounded short sides (a "stadium").
+pub fn capsule(width: f32, height: f32, segments: usize) -> Vec<Vertex2<f32>> {
+    rounded_rectangle(width, height, width.min(height) / 2.0, segments)
+}
+
+/// A polygon with `sides` equal sides - the first vertex points up.
+pub fn regular_polygon(radius: f32, sides: usize) -> Vec<Vertex2<f32>> {
+    wasm_assert!(sides > 2);
+
+    let radius = Vertex2::new(radius, radius);
+    (0..sides)
+        .map(|side| {
+            let angle = -FRAC_PI_2 + TAU * side as f32 / sides as f32;
+            point_on_ellipse(radius, angle)
+        })
+        .collect()
+}
+
+/// A star with `points` tips at `outer_radius` and the vertices between them at `inner_radius` -
+/// the first tip points up.
+pub fn star(outer_radius: f32, inner_radius: f32, points: usize) -> Vec<Vertex2<f32>> {
+    wasm_assert!(points > 1);
+
+    (0..points * 2)
+        .map(|vertex| {
+            let radius = if vertex % 2 == 0 {
+                outer_radius
+            } else {
+                inner_radius
+            };
+            let angle = -FRAC_PI_2 + PI * vertex as f32 / points as f32;
+            point_on_ellipse(Vertex2::new(radius, radius), angle)
+        })
+        .collect()
+}
+
+/// Compares vertices while tolerating floating point errors from the trigonometric functions.
+fn is_same_vertex(lhs: Vertex2<f32>, rhs: Vertex2<f32>) -> bool {
+    const EPSILON: f32 = 0.0001;
+    (lhs.x - rhs.x).abs() < EPSILON && (lhs.y - rhs.y).abs() < EPSILON
+}
+
+fn point_on_ellipse(radius: Vertex2<f32>, angle: f32) -> Vertex2<f32> {
+    let (sin, cos) = angle.sin_cos();
+    Vertex2::new(cos * radius.x, sin * radius.y)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::assert_near;
+    use std::f32::consts::FRAC_PI_4;
 
     #[test]
     fn rectangle() {
@@ -60,4 +202,118 @@ mod tests {
         assert_eq!(shape[2], Vertex2::new(SIZE / 2.0, SIZE / 2.0));
         assert_eq!(shape[3], Vertex2::new(-SIZE / 2.0, SIZE / 2.0));
     }
+
+    #[test]
+    fn circle() {
+        const RADIUS: f32 = 10.0;
+        let shape = super::circle(RADIUS, 4);
+
+        assert_eq!(shape.len(), 4);
+        assert_near(shape[0], Vertex2::new(RADIUS, 0.0));
+        assert_near(shape[1], Vertex2::new(0.0, RADIUS));
+        assert_near(shape[2], Vertex2::new(-RADIUS, 0.0));
+        assert_near(shape[3], Vertex2::new(0.0, -RADIUS));
+    }
+
+    #[test]
+    fn ellipse() {
+        const WIDTH: f32 = 40.0;
+        const HEIGHT: f32 = 20.0;
+        let shape = super::ellipse(WIDTH, HEIGHT, 32);
+
+        assert_eq!(shape.len(), 32);
+        assert_near(shape[0], Vertex2::new(WIDTH / 2.0, 0.0));
+        assert_near(shape[8], Vertex2::new(0.0, HEIGHT / 2.0));
+        assert_near(shape[16], Vertex2::new(-WIDTH / 2.0, 0.0));
+        assert_near(shape[24], Vertex2::new(0.0, -HEIGHT / 2.0));
+    }
+
+    #[test]
+    fn arc() {
+        const RADIUS: f32 = 10.0;
+        let shape = super::arc(RADIUS, 0.0, PI, 2);
+
+        assert_eq!(shape.len(), 3);
+        assert_near(shape[0], Vertex2::new(RADIUS, 0.0));
+        assert_near(shape[1], Vertex2::new(0.0, RADIUS));
+        assert_near(shape[2], Vertex2::new(-RADIUS, 0.0));
+    }
+
+    #[test]
+    fn pie() {
+        const RADIUS: f32 = 10.0;
+        let shape = super::pie(RADIUS, 0.0, FRAC_PI_2, 1);
+
+        assert_eq!(shape.len(), 3);
+        assert_eq!(shape[0], Vertex2::new(0.0, 0.0));
+        assert_near(shape[1], Vertex2::new(RADIUS, 0.0));
+        assert_near(shape[2], Vertex2::new(0.0, RADIUS));
+    }
+
+    #[test]
+    fn rounded_rectangle() {
+        const WIDTH: f32 = 200.0;
+        const HEIGHT: f32 = 50.0;
+        const RADIUS: f32 = 10.0;
+        let shape = super::rounded_rectangle(WIDTH, HEIGHT, RADIUS, 1);
+
+        assert_eq!(shape.len(), 8);
+        assert_near(shape[0], Vertex2::new(-WIDTH / 2.0, -HEIGHT / 2.0 + RADIUS));
+        assert_near(shape[1], Vertex2::new(-WIDTH / 2.0 + RADIUS, -HEIGHT / 2.0));
+        assert_near(shape[2], Vertex2::new(WIDTH / 2.0 - RADIUS, -HEIGHT / 2.0));
+        assert_near(shape[3], Vertex2::new(WIDTH / 2.0, -HEIGHT / 2.0 + RADIUS));
+        assert_near(shape[4], Vertex2::new(WIDTH / 2.0, HEIGHT / 2.0 - RADIUS));
+        assert_near(shape[5], Vertex2::new(WIDTH / 2.0 - RADIUS, HEIGHT / 2.0));
+        assert_near(shape[6], Vertex2::new(-WIDTH / 2.0 + RADIUS, HEIGHT / 2.0));
+        assert_near(shape[7], Vertex2::new(-WIDTH / 2.0, HEIGHT / 2.0 - RADIUS));
+    }
+
+    #[test]
+    fn capsule() {
+        const WIDTH: f32 = 100.0;
+        const HEIGHT: f32 = 20.0;
+        let shape = super::capsule(WIDTH, HEIGHT, 2);
+
+        // The zero-length left and right sides don't produce duplicate vertices
+        assert_eq!(shape.len(), 10);
+        assert_near(shape[0], Vertex2::new(-WIDTH / 2.0, 0.0));
+        assert_near(
+            shape[2],
+            Vertex2::new(-WIDTH / 2.0 + HEIGHT / 2.0, -HEIGHT / 2.0),
+        );
+        assert_near(shape[5], Vertex2::new(WIDTH / 2.0, 0.0));
+        assert_near(
+            shape[7],
+            Vertex2::new(WIDTH / 2.0 - HEIGHT / 2.0, HEIGHT / 2.0),
+        );
+    }
+
+    #[test]
+    fn regular_polygon() {
+        const RADIUS: f32 = 10.0;
+        let shape = super::regular_polygon(RADIUS, 4);
+
+        assert_eq!(shape.len(), 4);
+        assert_near(shape[0], Vertex2::new(0.0, -RADIUS));
+        assert_near(shape[1], Vertex2::new(RADIUS, 0.0));
+        assert_near(shape[2], Vertex2::new(0.0, RADIUS));
+        assert_near(shape[3], Vertex2::new(-RADIUS, 0.0));
+    }
+
+    #[test]
+    fn star() {
+        const OUTER_RADIUS: f32 = 20.0;
+        const INNER_RADIUS: f32 = 10.0;
+        let shape = super::star(OUTER_RADIUS, INNER_RADIUS, 4);
+
+        assert_eq!(shape.len(), 8);
+        assert_near(shape[0], Vertex2::new(0.0, -OUTER_RADIUS));
+        assert_near(shape[2], Vertex2::new(OUTER_RADIUS, 0.0));
+        assert_near(shape[4], Vertex2::new(0.0, OUTER_RADIUS));
+        assert_near(shape[6], Vertex2::new(-OUTER_RADIUS, 0.0));
+
+        let inner = INNER_RADIUS * FRAC_PI_4.cos();
+        assert_near(shape[1], Vertex2::new(inner, -inner));
+        assert_near(shape[5], Vertex2::new(-inner, inner));
+    }
 }
@@ -0,0 +1,13 @@
+//! Helpers shared by the unit tests.
+
+use crate::Vertex2;
+
+/// The tolerance for floating point errors (e.g. from the trigonometric functions).
+pub const EPSILON: f32 = 0.0001;
+
+pub fn assert_near(actual: Vertex2<f32>, expected: Vertex2<f32>) {
+    assert!(
+        (actual.x - expected.x).abs() < EPSILON && (actual.y - expected.y).abs() < EPSILON,
+        "{actual:?} != {expected:?}"
+    );
+}
@@ -0,0 +1,358 @@
+//! Grids of tiles - a [Tilemap](Tilemap) is a component that draws its [TileLayer](TileLayer)s
+//! (only the parts that are visible on the canvas) and keeps the [ObjectLayer](ObjectLayer)s of
+//! the level.
+//!
+//! Maps can be built manually or imported from the JSON format of the Tiled map editor (see
+//! [Tilemap::from_tiled]).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use twors::{
+//!     Result,
+//!     prelude::*,
+//!     tilemap::{Tile, Tilemap, Tileset},
+//! };
+//!
+//! fn level(ctx: &mut Context) -> Result<Tilemap> {
+//!     // 8 tiles of 16x16 pixels in rows of 4 - the first one is a wall
+//!     let image = ctx.assets.load_image("assets/tiles.png");
+//!     let tileset = Tileset::new(image, Vertex2::new(16.0, 16.0), 4, 8).with_solid(0);
+//!
+//!     let mut tilemap = Tilemap::new(100, 100, Vertex2::new(16.0, 16.0), vec![tileset])
+//!         .with_layer("ground", Layer::Five);
+//!     if let Some(ground) = tilemap.layer_mut("ground") {
+//!         ground.set(0, 0, Some(Tile::new(0, 0)))?;
+//!     }
+//!
+//!     assert!(tilemap.is_solid_at(Vertex2::new(8.0, 8.0)));
+//!     Ok(tilemap)
+//! }
+//! ```
+
+mod layer;
+mod object;
+mod tiled;
+mod tileset;
+
+pub use layer::{CHUNK_SIZE, Tile, TileLayer};
+pub use object::{MapObject, ObjectLayer, ObjectShape};
+pub use tileset::{TileFrame, TileInfo, Tileset};
+
+use crate::{ComponentLifecycle, Context, Layer, Renderable, Shape, Style, Transform, Vertex2};
+use std::{collections::HashMap, rc::Rc};
+use twors_derive::Component;
+
+/// The custom properties of a map, a layer, a tile or an object - by name.
+pub type Properties = HashMap<String, Property>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    /// Colors and file paths are strings as well
+    String(String),
+    /// A property with nested properties
+    Class(Properties),
+}
+
+impl Property {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Property::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Property::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Integers are converted to floats.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Property::Float(value) => Some(*value),
+            Property::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Property::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// A component that draws a grid of tiles per [TileLayer](TileLayer) - add it as a child of the
+/// component that manages the level.
+///
+/// Every tile layer is a [Renderable](Renderable) with its own [Layer](Layer), so other
+/// renderables can be drawn in between the tile layers (e.g. the player between the ground and
+/// the treetops). The top left corner of the map is at the position of the component.
+#[derive(Component)]
+pub struct Tilemap {
+    pub properties: Properties,
+    pub object_layers: Vec<ObjectLayer>,
+
+    width: usize,
+    height: usize,
+    tile_size: Vertex2<f32>,
+    tilesets: Rc<[Tileset]>,
+
+    transform: Transform,
+    /// One per tile layer
+    renderables: Vec<Renderable>,
+}
+
+impl Tilemap {
+    /// An empty map of `width` by `height` tiles of the given size - add tile layers with
+    /// [with_layer](Self::with_layer).
+    pub fn new(
+        width: usize,
+        height: usize,
+        tile_size: Vertex2<f32>,
+        tilesets: Vec<Tileset>,
+    ) -> Self {
+        Self {
+            properties: Properties::default(),
+            object_layers: Vec::default(),
+            width,
+            height,
+            tile_size,
+            tilesets: tilesets.into(),
+            transform: Transform::default(),
+            renderables: Vec::default(),
+        }
+    }
+
+    /// Adds an empty tile layer - layers added later are drawn on top of the earlier ones within
+    /// the same [Layer](Layer).
+    pub fn with_layer(mut self, name: &str, layer: Layer) -> Self {
+        let tiles = self.tile_layer(name);
+        self.push_layer(tiles, Transform::default(), Style::default(), layer);
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn tile_size(&self) -> Vertex2<f32> {
+        self.tile_size
+    }
+
+    pub fn tilesets(&self) -> &[Tileset] {
+        &self.tilesets
+    }
+
+    pub fn layers(&self) -> impl Iterator<Item = &TileLayer> {
+        self.renderables
+            .iter()
+            .filter_map(|renderable| match &renderable.shape {
+                Shape::Tiles(layer) => Some(layer.as_ref()),
+                _ => None,
+            })
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers().find(|layer| layer.name == name)
+    }
+
+    /// The layer is only copied if a clone of its renderable is still around.
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.renderables
+            .iter_mut()
+            .find_map(|renderable| match &mut renderable.shape {
+                Shape::Tiles(layer) if layer.name == name => Some(Rc::make_mut(layer)),
+                _ => None,
+            })
+    }
+
+    pub fn object_layer(&self, name: &str) -> Option<&ObjectLayer> {
+        self.object_layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// The objects of every object layer.
+    pub fn objects(&self) -> impl Iterator<Item = &MapObject> {
+        self.object_layers
+            .iter()
+            .flat_map(|layer| layer.objects.iter())
+    }
+
+    /// The column and row of the cell at the position (in world coordinates).
+    pub fn cell_at(&self, position: Vertex2<f32>) -> Option<(usize, usize)> {
+        let local = self
+            .transform
+            .absolute_matrix()
+            .inverse()?
+            .transform_point(position);
+        layer::cell_at(local, self.tile_size, self.width, self.height)
+    }
+
+    /// Whether any layer has a solid tile at the given column and row.
+    pub fn is_solid(&self, x: usize, y: usize) -> bool {
+        self.layers().any(|layer| layer.is_solid(x, y))
+    }
+
+    /// Whether any layer has a solid tile at the position (in world coordinates).
+    pub fn is_solid_at(&self, position: Vertex2<f32>) -> bool {
+        self.cell_at(position)
+            .is_some_and(|(x, y)| self.is_solid(x, y))
+    }
+
+    fn tile_layer(&self, name: &str) -> TileLayer {
+        TileLayer::new(
+            name,
+            self.width,
+            self.height,
+            self.tile_size,
+            self.tilesets.clone(),
+        )
+    }
+
+    fn push_layer(&mut self, tiles: TileLayer, transform: Transform, style: Style, layer: Layer) {
+        self.renderables.push(
+            Renderable::new(Shape::Tiles(Rc::new(tiles)), layer)
+                .with_transform(transform)
+                .with_style(style),
+        );
+    }
+}
+
+/// Advances the animated tiles.
+impl ComponentLifecycle for Tilemap {
+    fn update(&mut self, ctx: &mut Context) {
+        for renderable in &mut self.renderables {
+            if let Shape::Tiles(layer) = &mut renderable.shape {
+                Rc::make_mut(layer).advance(ctx.delta_time());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AssetManager, DrawCommand, ImageHandle, MemoryLoader, TestEngine, dimensions::Dimensions,
+    };
+
+    fn tilemap(image: ImageHandle) -> Tilemap {
+        let frames = vec![
+            TileFrame {
+                tile: 2,
+                duration: 1.0,
+            },
+            TileFrame {
+                tile: 3,
+                duration: 1.0,
+            },
+        ];
+        let tileset = Tileset::new(image, Vertex2::new(10.0, 10.0), 2, 4)
+            .with_solid(1)
+            .with_animation(2, frames);
+
+        Tilemap::new(4, 4, Vertex2::new(10.0, 10.0), vec![tileset])
+            .with_layer("ground", Layer::Five)
+            .with_layer("walls", Layer::Four)
+    }
+
+    #[test]
+    fn layers() {
+        let mut tilemap = tilemap(ImageHandle::new(0, 0));
+        tilemap
+            .layer_mut("walls")
+            .unwrap()
+            .set(1, 2, Some(Tile::new(0, 1)))
+            .unwrap();
+
+        let names: Vec<&str> = tilemap.layers().map(|layer| layer.name.as_str()).collect();
+        assert_eq!(names, ["ground", "walls"]);
+        assert_eq!(
+            tilemap.layer("walls").unwrap().get(1, 2),
+            Some(Tile::new(0, 1))
+        );
+        assert!(tilemap.layer("roof").is_none());
+    }
+
+    #[test]
+    fn solid() {
+        let mut tilemap = tilemap(ImageHandle::new(0, 0))
+            .with_transform(Transform::from_position(Vertex2::new(100.0, 0.0)));
+        tilemap
+            .layer_mut("walls")
+            .unwrap()
+            .set(1, 2, Some(Tile::new(0, 1)))
+            .unwrap();
+        tilemap
+            .layer_mut("ground")
+            .unwrap()
+            .set(1, 1, Some(Tile::new(0, 0)))
+            .unwrap();
+
+        assert_eq!(tilemap.cell_at(Vertex2::new(115.0, 25.0)), Some((1, 2)));
+        assert_eq!(tilemap.cell_at(Vertex2::new(15.0, 25.0)), None);
+        assert!(tilemap.is_solid(1, 2));
+        assert!(tilemap.is_solid_at(Vertex2::new(115.0, 25.0)));
+        assert!(!tilemap.is_solid_at(Vertex2::new(115.0, 15.0)));
+    }
+
+    #[test]
+    fn animated_tiles() {
+        let mut assets = AssetManager::new(Box::new(MemoryLoader::default()));
+        let mut tilemap = tilemap(assets.load_image("tiles.png"));
+        tilemap
+            .layer_mut("ground")
+            .unwrap()
+            .set(0, 0, Some(Tile::new(0, 2)))
+            .unwrap();
+        let mut engine = TestEngine::new(vec![Box::new(tilemap)]).with_assets(assets);
+        engine.set_image_size("tiles.png", Vertex2::new(20.0, 20.0));
+
+        let drawn_sources = |engine: &TestEngine| -> Vec<Dimensions> {
+            engine
+                .commands()
+                .iter()
+                .filter_map(|command| match command {
+                    DrawCommand::DrawImage { source, .. } => Some(*source),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        engine.step(0.5).unwrap();
+        assert_eq!(
+            drawn_sources(&engine),
+            [Dimensions::from_top_left(
+                Vertex2::new(0.0, 10.0),
+                10.0,
+                10.0
+            )]
+        );
+
+        engine.step(1.0).unwrap();
+        assert_eq!(
+            drawn_sources(&engine),
+            [Dimensions::from_top_left(
+                Vertex2::new(10.0, 10.0),
+                10.0,
+                10.0
+            )]
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use super::{Properties, Tileset};
+use crate::{Vertex2, dimensions::Dimensions, error::Result};
+use std::rc::Rc;
+
+/// The width and height of a chunk in tiles.
+pub const CHUNK_SIZE: usize = 16;
+
+/// A cell of a [TileLayer](TileLayer) - the tile at `index` of the tileset at `tileset`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Tile {
+    /// The index of the tileset within the tilemap
+    pub tileset: usize,
+    /// The index of the tile within the tileset
+    pub index: u32,
+
+    /// Mirrors the tile horizontally
+    pub flip_x: bool,
+    /// Mirrors the tile vertically
+    pub flip_y: bool,
+    /// Swaps the `x` and `y` axes of the tile - it's applied before `flip_x` and `flip_y` (which
+    /// together rotate the tile by 90 degree steps)
+    pub flip_diagonal: bool,
+}
+
+impl Tile {
+    pub fn new(tileset: usize, index: u32) -> Self {
+        Self {
+            tileset,
+            index,
+            flip_x: false,
+            flip_y: false,
+            flip_diagonal: false,
+        }
+    }
+}
+
+/// A square of `CHUNK_SIZE` tiles - chunks are the unit of culling.
+#[derive(Clone, Debug, PartialEq)]
+struct Chunk {
+    tiles: Vec<Option<Tile>>,
+    /// The number of non-empty tiles - empty chunks are skipped entirely
+    count: usize,
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self {
+            tiles: vec![None; CHUNK_SIZE * CHUNK_SIZE],
+            count: 0,
+        }
+    }
+}
+
+/// A grid of tiles that's rendered as a single [Shape](crate::Shape) - only the chunks that are
+/// visible on the canvas are drawn.
+///
+/// The top left corner of the tile at `(0, 0)` is at the origin of the layer and the `y` axis
+/// points down (like the canvas).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileLayer {
+    pub name: String,
+
+    /// Invisible layers are not drawn
+    pub visible: bool,
+
+    pub properties: Properties,
+
+    width: usize,
+    height: usize,
+    tile_size: Vertex2<f32>,
+    tilesets: Rc<[Tileset]>,
+
+    /// Row by row
+    chunks: Vec<Chunk>,
+
+    /// The clock of the animated tiles in seconds
+    time: f32,
+}
+
+impl TileLayer {
+    /// An empty layer of `width` by `height` tiles of the given size.
+    pub fn new(
+        name: &str,
+        width: usize,
+        height: usize,
+        tile_size: Vertex2<f32>,
+        tilesets: Rc<[Tileset]>,
+    ) -> Self {
+        let chunk_count = width.div_ceil(CHUNK_SIZE) * height.div_ceil(CHUNK_SIZE);
+        Self {
+            name: name.to_owned(),
+            visible: true,
+            properties: Properties::default(),
+            width,
+            height,
+            tile_size,
+            tilesets,
+            chunks: vec![Chunk::default(); chunk_count],
+            time: 0.0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn tile_size(&self) -> Vertex2<f32> {
+        self.tile_size
+    }
+
+    pub fn tilesets(&self) -> &[Tileset] {
+        &self.tilesets
+    }
+
+    /// The tile at the given column and row - `None` for empty and out of bounds cells.
+    pub fn get(&self, x: usize, y: usize) -> Option<Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let (chunk, index) = self.chunk_index(x, y);
+        self.chunks[chunk].tiles[index]
+    }
+
+    /// Fails if the cell is out of bounds or if the layer has no tileset at the `tileset` index
+    /// of the tile.
+    pub fn set(&mut self, x: usize, y: usize, tile: Option<Tile>) -> Result<()> {
+        if x >= self.width || y >= self.height {
+            return Err(format!("The cell ({x}, {y}) is out of the bounds of the layer").into());
+        }
+        if let Some(tile) = tile
+            && tile.tileset >= self.tilesets.len()
+        {
+            return Err(format!("The layer has no tileset {}", tile.tileset).into());
+        }
+
+        let (chunk, index) = self.chunk_index(x, y);
+        let chunk = &mut self.chunks[chunk];
+        match (chunk.tiles[index].is_some(), tile.is_some()) {
+            (false, true) => chunk.count += 1,
+            (true, false) => chunk.count -= 1,
+            _ => {}
+        }
+        chunk.tiles[index] = tile;
+        Ok(())
+    }
+
+    /// Whether the tile at the given column and row is solid - see [TileInfo](super::TileInfo).
+    pub fn is_solid(&self, x: usize, y: usize) -> bool {
+        self.get(x, y)
+            .is_some_and(|tile| self.tilesets[tile.tileset].is_solid(tile.index))
+    }
+
+    /// The column and row of the cell that contains the point (in the local space of the layer).
+    pub fn cell_at(&self, point: Vertex2<f32>) -> Option<(usize, usize)> {
+        cell_at(point, self.tile_size, self.width, self.height)
+    }
+
+    /// The column and row of the non-empty chunks that overlap the area (in the local space of
+    /// the layer).
+    pub fn visible_chunks(&self, area: &Dimensions) -> Vec<(usize, usize)> {
+        let chunk_width = CHUNK_SIZE as f32 * self.tile_size.x;
+        let chunk_height = CHUNK_SIZE as f32 * self.tile_size.y;
+        let columns = self.width.div_ceil(CHUNK_SIZE);
+        let rows = self.height.div_ceil(CHUNK_SIZE);
+
+        // Clamped before the conversion, since the area can be infinitely far away
+        let range = |start: f32, end: f32, size: f32, count: usize| {
+            let first = (start / size).floor().clamp(0.0, count as f32) as usize;
+            let last = (end / size).ceil().clamp(0.0, count as f32) as usize;
+            first..last
+        };
+        let column_range = range(area.left(), area.right(), chunk_width, columns);
+        let row_range = range(area.top(), area.bottom(), chunk_height, rows);
+
+        row_range
+            .flat_map(|row| column_range.clone().map(move |column| (column, row)))
+            .filter(|(column, row)| self.chunks[row * columns + column].count > 0)
+            .collect()
+    }
+
+    /// The non-empty tiles of the chunk along with their column and row.
+    pub(crate) fn chunk_tiles(
+        &self,
+        column: usize,
+        row: usize,
+    ) -> impl Iterator<Item = (usize, usize, Tile)> {
+        let columns = self.width.div_ceil(CHUNK_SIZE);
+        let chunk = &self.chunks[row * columns + column];
+        chunk
+            .tiles
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, tile)| {
+                let x = column * CHUNK_SIZE + index % CHUNK_SIZE;
+                let y = row * CHUNK_SIZE + index / CHUNK_SIZE;
+                tile.map(|tile| (x, y, tile))
+            })
+    }
+
+    pub(crate) fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub(crate) fn advance(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    /// Returns the index of the chunk and the index of the tile within the chunk.
+    fn chunk_index(&self, x: usize, y: usize) -> (usize, usize) {
+        let columns = self.width.div_ceil(CHUNK_SIZE);
+        let chunk = (y / CHUNK_SIZE) * columns + x / CHUNK_SIZE;
+        (chunk, (y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE)
+    }
+}
+
+/// The column and row of the cell of a `width` by `height` grid that contains the point.
+pub(super) fn cell_at(
+    point: Vertex2<f32>,
+    tile_size: Vertex2<f32>,
+    width: usize,
+    height: usize,
+) -> Option<(usize, usize)> {
+    let x = (point.x / tile_size.x).floor();
+    let y = (point.y / tile_size.y).floor();
+    let in_bounds = x >= 0.0 && y >= 0.0 && x < width as f32 && y < height as f32;
+    in_bounds.then_some((x as usize, y as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImageHandle;
+
+    fn layer(width: usize, height: usize) -> TileLayer {
+        let tileset =
+            Tileset::new(ImageHandle::new(0, 0), Vertex2::new(10.0, 10.0), 2, 4).with_solid(1);
+        TileLayer::new(
+            "ground",
+            width,
+            height,
+            Vertex2::new(10.0, 10.0),
+            Rc::from([tileset]),
+        )
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut layer = layer(40, 20);
+        layer.set(17, 3, Some(Tile::new(0, 1))).unwrap();
+
+        assert_eq!(layer.get(17, 3), Some(Tile::new(0, 1)));
+        assert_eq!(layer.get(3, 17), None);
+        assert_eq!(layer.get(100, 3), None);
+        assert!(layer.is_solid(17, 3));
+        assert!(!layer.is_solid(0, 0));
+    }
+
+    #[test]
+    fn set_invalid() {
+        let mut layer = layer(4, 4);
+
+        assert!(layer.set(4, 0, Some(Tile::new(0, 0))).is_err());
+        assert!(layer.set(0, 0, Some(Tile::new(1, 0))).is_err());
+        assert_eq!(layer.get(0, 0), None);
+    }
+
+    #[test]
+    fn cell_at() {
+        let layer = layer(4, 4);
+
+        assert_eq!(layer.cell_at(Vertex2::new(15.0, 39.0)), Some((1, 3)));
+        assert_eq!(layer.cell_at(Vertex2::new(-1.0, 0.0)), None);
+        assert_eq!(layer.cell_at(Vertex2::new(0.0, 40.0)), None);
+    }
+
+    #[test]
+    fn visible_chunks() {
+        // 3x2 chunks of 160x160
+        let mut layer = layer(40, 20);
+        for (x, y) in [(0, 0), (20, 0), (39, 19), (5, 19)] {
+            layer.set(x, y, Some(Tile::new(0, 0))).unwrap();
+        }
+
+        let everything = Dimensions::from_top_left(Vertex2::new(-100.0, -100.0), 1000.0, 1000.0);
+        assert_eq!(
+            layer.visible_chunks(&everything),
+            [(0, 0), (1, 0), (0, 1), (2, 1)]
+        );
+
+        let corner = Dimensions::from_top_left(Vertex2::new(150.0, 150.0), 20.0, 20.0);
+        assert_eq!(layer.visible_chunks(&corner), [(0, 0), (1, 0), (0, 1)]);
+
+        let outside = Dimensions::from_top_left(Vertex2::new(500.0, 0.0), 100.0, 100.0);
+        assert!(layer.visible_chunks(&outside).is_empty());
+    }
+
+    #[test]
+    fn empty_chunks_are_skipped() {
+        let mut layer = layer(16, 16);
+        layer.set(1, 1, Some(Tile::new(0, 0))).unwrap();
+        layer.set(1, 1, None).unwrap();
+
+        let area = Dimensions::from_top_left(Vertex2::default(), 160.0, 160.0);
+        assert!(layer.visible_chunks(&area).is_empty());
+    }
+
+    #[test]
+    fn chunk_tiles() {
+        let mut layer = layer(40, 20);
+        layer.set(17, 3, Some(Tile::new(0, 2))).unwrap();
+        layer.set(31, 15, Some(Tile::new(0, 3))).unwrap();
+
+        let tiles: Vec<_> = layer.chunk_tiles(1, 0).collect();
+        assert_eq!(tiles, [(17, 3, Tile::new(0, 2)), (31, 15, Tile::new(0, 3))]);
+    }
+}
@@ -0,0 +1,56 @@
+use super::{Properties, Tile};
+use crate::Vertex2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectShape {
+    Rectangle,
+    /// Fits the rectangle of the object
+    Ellipse,
+    Point,
+    /// A closed polygon - the vertices are relative to the position of the object
+    Polygon(Vec<Vertex2<f32>>),
+    /// An open line - the vertices are relative to the position of the object
+    Polyline(Vec<Vertex2<f32>>),
+    Text(String),
+}
+
+/// A free-form object placed on the map (e.g. a spawn point, a trigger area or a collider) - the
+/// engine doesn't draw objects, it's up to the components to interpret them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    /// The user-defined type of the object (e.g. `"enemy"`)
+    pub class: String,
+
+    /// The top left corner of the rectangle, or the bottom left corner for tile objects (the same
+    /// as in Tiled)
+    pub position: Vertex2<f32>,
+    pub size: Vertex2<f32>,
+    /// Clockwise, in radians
+    pub rotation: f32,
+
+    pub visible: bool,
+    pub shape: ObjectShape,
+
+    /// The tile displayed by tile objects
+    pub tile: Option<Tile>,
+
+    pub properties: Properties,
+}
+
+/// A named group of [MapObject](MapObject)s.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub visible: bool,
+    pub objects: Vec<MapObject>,
+    pub properties: Properties,
+}
+
+impl ObjectLayer {
+    /// The first object with the given name.
+    pub fn object(&self, name: &str) -> Option<&MapObject> {
+        self.objects.iter().find(|object| object.name == name)
+    }
+}
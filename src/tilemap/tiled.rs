@@ -0,0 +1,633 @@
+//! An importer for the JSON map format of the Tiled map editor - see
+//! <https://doc.mapeditor.org/en/stable/reference/json-map-format/>.
+
+use super::{
+    MapObject, ObjectLayer, ObjectShape, Properties, Property, Tile, TileFrame, TileInfo, Tilemap,
+    Tileset,
+};
+use crate::{AssetManager, Layer, Style, Transform, Vertex2, error::Result};
+use serde_json::Value;
+use std::ops::Range;
+
+/// The flags in the highest bits of a global tile id
+const FLIP_X: u32 = 0x8000_0000;
+const FLIP_Y: u32 = 0x4000_0000;
+const FLIP_DIAGONAL: u32 = 0x2000_0000;
+/// Only used by hexagonal maps
+const ROTATE_HEXAGONAL: u32 = 0x1000_0000;
+
+/// The [Layer](Layer) of the tile layers that don't have a `layer` property.
+const DEFAULT_LAYER: Layer = Layer::Five;
+
+/// The offset, opacity and visibility that group layers pass on to their children.
+#[derive(Copy, Clone)]
+struct Group {
+    offset: Vertex2<f32>,
+    alpha: f32,
+    visible: bool,
+}
+
+impl Tilemap {
+    /// Imports a map saved in the JSON format of Tiled (`.tmj`) and starts loading the images of
+    /// its tilesets - the image paths are relative to the `directory` of the map file.
+    ///
+    /// - the map has to be orthogonal and finite (not infinite)
+    /// - tilesets have to be embedded in the map and based on a single image
+    /// - the tile layer format has to be CSV (the default)
+    /// - tile layers are drawn in the [Layer](Layer) of their `layer` integer property
+    ///   (`Layer::Five` by default) - within the same layer they're drawn in the order of the map
+    /// - tiles with a `solid` boolean property or a collision shape are solid
+    /// - group layers are flattened, while image layers are skipped
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use twors::{prelude::*, tilemap::Tilemap};
+    ///
+    /// fn load_level(ctx: &mut Context) -> Option<Tilemap> {
+    ///     let map = ctx.assets.load::<serde_json::Value>("assets/maps/level.tmj");
+    ///     // The JSON is cloned, since importing the map loads the tileset images
+    ///     let json = ctx.assets.get(map).cloned()?;
+    ///
+    ///     Tilemap::from_tiled(&json, "assets/maps", ctx.assets)
+    ///         .inspect_err(|err| log::error!("Invalid level: {err}"))
+    ///         .ok()
+    /// }
+    /// ```
+    pub fn from_tiled(json: &Value, directory: &str, assets: &mut AssetManager) -> Result<Self> {
+        let orientation = json["orientation"].as_str().unwrap_or("orthogonal");
+        if orientation != "orthogonal" {
+            return Err(
+                format!("The map is {orientation} - only orthogonal maps are supported").into(),
+            );
+        }
+        if json["infinite"].as_bool() == Some(true) {
+            return Err("The map is infinite - only finite maps are supported".into());
+        }
+
+        let mut tileset_ids = Vec::default();
+        let mut tilesets = Vec::default();
+        for json in json["tilesets"].as_array().into_iter().flatten() {
+            let first_id = number(json, "firstgid", "tileset")? as u32;
+            let tileset = parse_tileset(json, directory, assets)?;
+            let end = first_id
+                .checked_add(tileset.tile_count)
+                .ok_or_else(|| format!("The tileset {} has too many tiles", tileset.name))?;
+            tileset_ids.push(first_id..end);
+            tilesets.push(tileset);
+        }
+
+        let width = number(json, "width", "map")? as usize;
+        let height = number(json, "height", "map")? as usize;
+        let tile_size = Vertex2::new(
+            number(json, "tilewidth", "map")? as f32,
+            number(json, "tileheight", "map")? as f32,
+        );
+
+        let mut tilemap = Tilemap::new(width, height, tile_size, tilesets);
+        tilemap.properties = parse_properties(json)?;
+
+        let group = Group {
+            offset: Vertex2::default(),
+            alpha: 1.0,
+            visible: true,
+        };
+        tilemap.add_tiled_layers(&json["layers"], group, &tileset_ids)?;
+
+        Ok(tilemap)
+    }
+
+    fn add_tiled_layers(
+        &mut self,
+        layers: &Value,
+        parent: Group,
+        tileset_ids: &[Range<u32>],
+    ) -> Result<()> {
+        for json in layers.as_array().into_iter().flatten() {
+            let name = json["name"].as_str().unwrap_or_default();
+            let group = Group {
+                offset: parent.offset
+                    + Vertex2::new(
+                        json["offsetx"].as_f64().unwrap_or_default() as f32,
+                        json["offsety"].as_f64().unwrap_or_default() as f32,
+                    ),
+                alpha: parent.alpha * json["opacity"].as_f64().unwrap_or(1.0) as f32,
+                visible: parent.visible && json["visible"].as_bool().unwrap_or(true),
+            };
+
+            match json["type"].as_str().unwrap_or_default() {
+                "tilelayer" => self.add_tiled_tile_layer(json, name, group, tileset_ids)?,
+                "objectgroup" => {
+                    let objects = json["objects"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(|object| parse_object(object, group.offset, tileset_ids))
+                        .collect::<Result<Vec<MapObject>>>()?;
+
+                    self.object_layers.push(ObjectLayer {
+                        name: name.to_owned(),
+                        visible: group.visible,
+                        objects,
+                        properties: parse_properties(json)?,
+                    });
+                }
+                "group" => self.add_tiled_layers(&json["layers"], group, tileset_ids)?,
+                "imagelayer" => log::warn!("Skipped the image layer {name} - not supported"),
+                layer_type => {
+                    return Err(format!("The layer {name} has an unknown type {layer_type}").into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_tiled_tile_layer(
+        &mut self,
+        json: &Value,
+        name: &str,
+        group: Group,
+        tileset_ids: &[Range<u32>],
+    ) -> Result<()> {
+        if json["encoding"]
+            .as_str()
+            .is_some_and(|encoding| encoding != "csv")
+        {
+            return Err(
+                format!("The tile layer {name} is encoded - use the CSV layer format").into(),
+            );
+        }
+
+        let data = json["data"]
+            .as_array()
+            .ok_or_else(|| format!("The tile layer {name} has no data"))?;
+        if data.len() != self.width * self.height {
+            return Err(format!("The tile layer {name} doesn't match the size of the map").into());
+        }
+
+        let mut tiles = self.tile_layer(name);
+        tiles.visible = group.visible;
+        tiles.properties = parse_properties(json)?;
+        for (index, id) in data.iter().enumerate() {
+            let id = id
+                .as_u64()
+                .ok_or_else(|| format!("The tile layer {name} has an invalid tile"))?;
+            if let Some(tile) = tile(id as u32, tileset_ids)? {
+                tiles.set(index % self.width, index / self.width, Some(tile))?;
+            }
+        }
+
+        let layer = match tiles.properties.get("layer").and_then(Property::as_int) {
+            Some(layer) => Layer(layer as i32),
+            None => DEFAULT_LAYER,
+        };
+        self.push_layer(
+            tiles,
+            Transform::from_position(group.offset),
+            Style::default().with_alpha(group.alpha),
+            layer,
+        );
+
+        Ok(())
+    }
+}
+
+fn parse_tileset(json: &Value, directory: &str, assets: &mut AssetManager) -> Result<Tileset> {
+    if let Some(source) = json["source"].as_str() {
+        return Err(format!("The tileset {source} is external - embed it in the map").into());
+    }
+
+    let name = json["name"].as_str().unwrap_or_default();
+    let image = json["image"].as_str().ok_or_else(|| {
+        format!("The tileset {name} has no image - image collections are not supported")
+    })?;
+    let image = match directory.trim_end_matches('/') {
+        "" => image.to_owned(),
+        directory => format!("{directory}/{image}"),
+    };
+
+    let tile_size = Vertex2::new(
+        number(json, "tilewidth", "tileset")? as f32,
+        number(json, "tileheight", "tileset")? as f32,
+    );
+    let columns = number(json, "columns", "tileset")? as u32;
+    if columns == 0 {
+        return Err(format!("The tileset {name} has no columns").into());
+    }
+    let tile_count = number(json, "tilecount", "tileset")? as u32;
+    let mut tileset = Tileset::new(assets.load_image(&image), tile_size, columns, tile_count)
+        .with_name(name)
+        .with_spacing(
+            json["margin"].as_f64().unwrap_or_default() as f32,
+            json["spacing"].as_f64().unwrap_or_default() as f32,
+        );
+
+    let tile_id = |json: &Value, key: &str, owner: &str| -> Result<u32> {
+        let id = number(json, key, owner)? as u32;
+        if id >= tile_count {
+            return Err(format!("The tileset {name} has no tile {id}").into());
+        }
+        Ok(id)
+    };
+    for tile in json["tiles"].as_array().into_iter().flatten() {
+        let id = tile_id(tile, "id", "tile")?;
+        let properties = parse_properties(tile)?;
+        let has_collision = tile["objectgroup"]["objects"]
+            .as_array()
+            .is_some_and(|objects| !objects.is_empty());
+        let animation = tile["animation"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|frame| {
+                Ok(TileFrame {
+                    tile: tile_id(frame, "tileid", "frame")?,
+                    duration: number(frame, "duration", "frame")? as f32 / 1000.0,
+                })
+            })
+            .collect::<Result<Vec<TileFrame>>>()?;
+
+        let info = TileInfo {
+            solid: has_collision
+                || properties.get("solid").and_then(Property::as_bool) == Some(true),
+            animation,
+            properties,
+        };
+        tileset.tiles.insert(id, info);
+    }
+
+    Ok(tileset)
+}
+
+fn parse_object(
+    json: &Value,
+    offset: Vertex2<f32>,
+    tileset_ids: &[Range<u32>],
+) -> Result<MapObject> {
+    let id = number(json, "id", "object")? as u32;
+    if json["template"].is_string() {
+        return Err(
+            format!("The object {id} uses a template - templates are not supported").into(),
+        );
+    }
+
+    let vertices = |key: &str| {
+        json[key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|point| {
+                Ok(Vertex2::new(
+                    number(point, "x", "point")? as f32,
+                    number(point, "y", "point")? as f32,
+                ))
+            })
+            .collect::<Result<Vec<Vertex2<f32>>>>()
+    };
+    let shape = if json["point"].as_bool() == Some(true) {
+        ObjectShape::Point
+    } else if json["ellipse"].as_bool() == Some(true) {
+        ObjectShape::Ellipse
+    } else if json["polygon"].is_array() {
+        ObjectShape::Polygon(vertices("polygon")?)
+    } else if json["polyline"].is_array() {
+        ObjectShape::Polyline(vertices("polyline")?)
+    } else if let Some(text) = json["text"]["text"].as_str() {
+        ObjectShape::Text(text.to_owned())
+    } else {
+        ObjectShape::Rectangle
+    };
+
+    let field = |key: &str| json[key].as_f64().unwrap_or_default() as f32;
+    let tile = match json["gid"].as_u64() {
+        Some(id) => tile(id as u32, tileset_ids)?,
+        None => None,
+    };
+    // Tiled 1.9 named the type of the object "class"
+    let class = json["type"].as_str().or(json["class"].as_str());
+
+    Ok(MapObject {
+        id,
+        name: json["name"].as_str().unwrap_or_default().to_owned(),
+        class: class.unwrap_or_default().to_owned(),
+        position: offset + Vertex2::new(field("x"), field("y")),
+        size: Vertex2::new(field("width"), field("height")),
+        rotation: field("rotation").to_radians(),
+        visible: json["visible"].as_bool().unwrap_or(true),
+        shape,
+        tile,
+        properties: parse_properties(json)?,
+    })
+}
+
+/// Turns a global tile id into a [Tile](Tile) - `None` for the empty id `0`. The `tileset_ids`
+/// are the global ids of the tiles of each tileset.
+fn tile(id: u32, tileset_ids: &[Range<u32>]) -> Result<Option<Tile>> {
+    let index = id & !(FLIP_X | FLIP_Y | FLIP_DIAGONAL | ROTATE_HEXAGONAL);
+    if index == 0 {
+        return Ok(None);
+    }
+
+    // The tileset with the highest first id that's not above the id
+    let tileset = tileset_ids
+        .iter()
+        .rposition(|ids| ids.start <= index)
+        .filter(|tileset| tileset_ids[*tileset].contains(&index))
+        .ok_or_else(|| format!("The tile {index} doesn't belong to any tileset"))?;
+
+    Ok(Some(Tile {
+        tileset,
+        index: index - tileset_ids[tileset].start,
+        flip_x: id & FLIP_X != 0,
+        flip_y: id & FLIP_Y != 0,
+        flip_diagonal: id & FLIP_DIAGONAL != 0,
+    }))
+}
+
+fn parse_properties(json: &Value) -> Result<Properties> {
+    json["properties"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|property| {
+            let name = property["name"].as_str().ok_or("A property has no name")?;
+            Ok((name.to_owned(), property_value(&property["value"])))
+        })
+        .collect()
+}
+
+/// The type of the property is inferred from the JSON value, since the values of class
+/// properties don't have types.
+fn property_value(value: &Value) -> Property {
+    match value {
+        Value::Bool(value) => Property::Bool(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => Property::Int(value),
+            None => Property::Float(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => Property::String(value.clone()),
+        Value::Object(members) => Property::Class(
+            members
+                .iter()
+                .map(|(name, value)| (name.clone(), property_value(value)))
+                .collect(),
+        ),
+        Value::Null | Value::Array(_) => Property::String(value.to_string()),
+    }
+}
+
+fn number(json: &Value, key: &str, owner: &str) -> Result<f64> {
+    json[key]
+        .as_f64()
+        .ok_or_else(|| format!("The {owner} has no {key}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryLoader, Shape};
+
+    const MAP: &str = r#"{
+        "orientation": "orthogonal",
+        "width": 3,
+        "height": 2,
+        "tilewidth": 16,
+        "tileheight": 16,
+        "properties": [{ "name": "music", "type": "file", "value": "forest.ogg" }],
+        "tilesets": [
+            {
+                "firstgid": 1,
+                "name": "terrain",
+                "image": "terrain.png",
+                "tilewidth": 16,
+                "tileheight": 16,
+                "columns": 4,
+                "tilecount": 8,
+                "margin": 1,
+                "spacing": 2,
+                "tiles": [
+                    { "id": 1, "properties": [{ "name": "solid", "type": "bool", "value": true }] },
+                    {
+                        "id": 2,
+                        "objectgroup": { "objects": [{ "id": 1, "x": 0, "y": 0, "width": 16, "height": 8 }] }
+                    },
+                    { "id": 4, "animation": [{ "tileid": 4, "duration": 100 }, { "tileid": 5, "duration": 300 }] }
+                ]
+            },
+            {
+                "firstgid": 9,
+                "name": "props",
+                "image": "../props.png",
+                "tilewidth": 16,
+                "tileheight": 32,
+                "columns": 2,
+                "tilecount": 2
+            }
+        ],
+        "layers": [
+            { "type": "tilelayer", "name": "ground", "data": [1, 2, 3, 0, 5, 10], "opacity": 0.5 },
+            {
+                "type": "group",
+                "name": "decoration",
+                "offsetx": 10,
+                "layers": [
+                    {
+                        "type": "tilelayer",
+                        "name": "roof",
+                        "data": [2147483657, 0, 0, 0, 0, 1073741825],
+                        "properties": [{ "name": "layer", "type": "int", "value": 1 }]
+                    },
+                    {
+                        "type": "objectgroup",
+                        "name": "spawns",
+                        "offsety": 5,
+                        "objects": [
+                            { "id": 1, "name": "player", "type": "spawn", "x": 8, "y": 8, "point": true },
+                            { "id": 2, "name": "door", "x": 32, "y": 0, "width": 16, "height": 16, "rotation": 90 },
+                            {
+                                "id": 3,
+                                "name": "path",
+                                "x": 0,
+                                "y": 0,
+                                "polyline": [{ "x": 0, "y": 0 }, { "x": 16, "y": 8 }],
+                                "properties": [{ "name": "speed", "type": "float", "value": 1.5 }]
+                            },
+                            { "id": 4, "gid": 9, "x": 0, "y": 32, "width": 16, "height": 32 }
+                        ]
+                    }
+                ]
+            },
+            { "type": "imagelayer", "name": "sky", "image": "sky.png" }
+        ]
+    }"#;
+
+    fn import(json: &str) -> Result<Tilemap> {
+        let json = serde_json::from_str(json).unwrap();
+        let mut assets = AssetManager::new(Box::new(MemoryLoader::default()));
+        Tilemap::from_tiled(&json, "maps/", &mut assets)
+    }
+
+    #[test]
+    fn map() {
+        let tilemap = import(MAP).unwrap();
+
+        assert_eq!((tilemap.width(), tilemap.height()), (3, 2));
+        assert_eq!(tilemap.tile_size(), Vertex2::new(16.0, 16.0));
+        assert_eq!(
+            tilemap.properties["music"],
+            Property::String("forest.ogg".to_owned())
+        );
+    }
+
+    #[test]
+    fn tilesets() {
+        let tilemap = import(MAP).unwrap();
+        let [terrain, props] = tilemap.tilesets() else {
+            panic!("Expected two tilesets");
+        };
+
+        assert_eq!(terrain.name, "terrain");
+        assert_eq!((terrain.margin, terrain.spacing), (1.0, 2.0));
+        assert!(terrain.is_solid(1));
+        assert!(terrain.is_solid(2));
+        assert!(!terrain.is_solid(3));
+        assert_eq!(terrain.animated_tile(4, 0.2), 5);
+        assert_eq!(props.tile_size, Vertex2::new(16.0, 32.0));
+        assert_ne!(terrain.image, props.image);
+    }
+
+    #[test]
+    fn tile_layers() {
+        let tilemap = import(MAP).unwrap();
+        let ground = tilemap.layer("ground").unwrap();
+        let roof = tilemap.layer("roof").unwrap();
+
+        assert_eq!(ground.get(0, 0), Some(Tile::new(0, 0)));
+        assert_eq!(ground.get(0, 1), None);
+        assert_eq!(ground.get(2, 1), Some(Tile::new(1, 1)));
+        assert!(tilemap.is_solid(1, 0));
+        assert!(!tilemap.is_solid(0, 1));
+
+        let flipped_x = Tile {
+            flip_x: true,
+            ..Tile::new(1, 0)
+        };
+        let flipped_y = Tile {
+            flip_y: true,
+            ..Tile::new(0, 0)
+        };
+        assert_eq!(roof.get(0, 0), Some(flipped_x));
+        assert_eq!(roof.get(2, 1), Some(flipped_y));
+
+        let renderables = &tilemap.renderables;
+        assert_eq!(renderables.len(), 2);
+        assert_eq!(renderables[0].layer, DEFAULT_LAYER);
+        assert_eq!(renderables[0].style.alpha, 0.5);
+        assert_eq!(renderables[1].layer, Layer::One);
+        assert_eq!(renderables[1].transform.position, Vertex2::new(10.0, 0.0));
+        assert!(matches!(&renderables[1].shape, Shape::Tiles(layer) if layer.name == "roof"));
+    }
+
+    #[test]
+    fn object_layers() {
+        let tilemap = import(MAP).unwrap();
+        let spawns = tilemap.object_layer("spawns").unwrap();
+
+        let player = spawns.object("player").unwrap();
+        assert_eq!(player.class, "spawn");
+        assert_eq!(player.shape, ObjectShape::Point);
+        assert_eq!(player.position, Vertex2::new(18.0, 13.0));
+
+        let door = spawns.object("door").unwrap();
+        assert_eq!(door.shape, ObjectShape::Rectangle);
+        assert_eq!(door.size, Vertex2::new(16.0, 16.0));
+        assert_eq!(door.rotation, 90.0_f32.to_radians());
+
+        let path = spawns.object("path").unwrap();
+        assert_eq!(
+            path.shape,
+            ObjectShape::Polyline(vec![Vertex2::new(0.0, 0.0), Vertex2::new(16.0, 8.0)])
+        );
+        assert_eq!(path.properties["speed"].as_float(), Some(1.5));
+
+        assert_eq!(spawns.objects[3].tile, Some(Tile::new(1, 0)));
+        assert_eq!(tilemap.objects().count(), 4);
+    }
+
+    #[test]
+    fn unsupported() {
+        let map = |json: &str| {
+            let mut map: Value = serde_json::from_str(MAP).unwrap();
+            let patch: Value = serde_json::from_str(json).unwrap();
+            for (key, value) in patch.as_object().unwrap() {
+                map[key] = value.clone();
+            }
+            map.to_string()
+        };
+
+        assert!(import(&map(r#"{ "orientation": "isometric" }"#)).is_err());
+        assert!(import(&map(r#"{ "infinite": true }"#)).is_err());
+        assert!(
+            import(&map(
+                r#"{ "tilesets": [{ "firstgid": 1, "source": "terrain.tsj" }] }"#
+            ))
+            .is_err()
+        );
+        assert!(
+            import(&map(
+                r#"{ "layers": [{ "type": "tilelayer", "name": "a", "data": "AAAA", "encoding": "base64" }] }"#
+            ))
+            .is_err()
+        );
+        assert!(
+            import(&map(
+                r#"{ "layers": [{ "type": "tilelayer", "name": "a", "data": [1, 2] }] }"#
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn invalid_tilesets() {
+        let map = |tileset: &str| {
+            let mut map: Value = serde_json::from_str(MAP).unwrap();
+            let patch: Value = serde_json::from_str(tileset).unwrap();
+            for (key, value) in patch.as_object().unwrap() {
+                map["tilesets"][1][key] = value.clone();
+            }
+            map.to_string()
+        };
+
+        assert!(import(&map(r#"{ "columns": 0 }"#)).is_err());
+        assert!(import(&map(r#"{ "tiles": [{ "id": 2, "properties": [] }] }"#)).is_err());
+        assert!(
+            import(&map(
+                r#"{ "tiles": [{ "id": 0, "animation": [{ "tileid": 5, "duration": 100 }] }] }"#
+            ))
+            .is_err()
+        );
+        // The last tile of the ground layer is the second tile of the tileset
+        assert!(import(&map(r#"{ "tilecount": 1 }"#)).is_err());
+        // The ids of the tileset would overflow
+        assert!(import(&map(r#"{ "firstgid": 4294967295 }"#)).is_err());
+    }
+
+    #[test]
+    fn tile_ids() {
+        let tileset_ids = [1..9, 9..11];
+
+        assert_eq!(tile(0, &tileset_ids).unwrap(), None);
+        assert_eq!(tile(8, &tileset_ids).unwrap(), Some(Tile::new(0, 7)));
+        assert_eq!(tile(10, &tileset_ids).unwrap(), Some(Tile::new(1, 1)));
+        assert_eq!(
+            tile(FLIP_DIAGONAL | 1, &tileset_ids).unwrap(),
+            Some(Tile {
+                flip_diagonal: true,
+                ..Tile::new(0, 0)
+            })
+        );
+        assert!(tile(1, &tileset_ids[1..]).is_err());
+        assert!(tile(11, &tileset_ids).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use super::Properties;
+use crate::{ImageHandle, Vertex2, dimensions::Dimensions, wasm_assert};
+use std::collections::HashMap;
+
+/// A frame of an animated tile.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TileFrame {
+    /// The index of the tile to display within the tileset
+    pub tile: u32,
+
+    /// In seconds
+    pub duration: f32,
+}
+
+/// The data of a single tile of a [Tileset](Tileset) - tiles without any data don't need one.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TileInfo {
+    /// Solid tiles block movement - see [Tilemap::is_solid](super::Tilemap::is_solid)
+    pub solid: bool,
+
+    /// The frames of an animated tile - empty for static tiles
+    pub animation: Vec<TileFrame>,
+
+    pub properties: Properties,
+}
+
+/// An image split into a grid of tiles of the same size - the tiles are indexed left to right,
+/// top to bottom.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tileset {
+    pub name: String,
+    pub image: ImageHandle,
+
+    /// The size of a tile in image pixels
+    pub tile_size: Vertex2<f32>,
+    pub columns: u32,
+    pub tile_count: u32,
+
+    /// The space around the grid in image pixels
+    pub margin: f32,
+    /// The space between the tiles in image pixels
+    pub spacing: f32,
+
+    /// The tiles that are solid, animated or have properties - by tile index
+    pub tiles: HashMap<u32, TileInfo>,
+}
+
+impl Tileset {
+    pub fn new(image: ImageHandle, tile_size: Vertex2<f32>, columns: u32, tile_count: u32) -> Self {
+        wasm_assert!(columns > 0);
+
+        Self {
+            name: String::default(),
+            image,
+            tile_size,
+            columns,
+            tile_count,
+            margin: 0.0,
+            spacing: 0.0,
+            tiles: HashMap::default(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_owned();
+        self
+    }
+
+    pub fn with_spacing(mut self, margin: f32, spacing: f32) -> Self {
+        self.margin = margin;
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_solid(mut self, tile: u32) -> Self {
+        self.tiles.entry(tile).or_default().solid = true;
+        self
+    }
+
+    pub fn with_animation(mut self, tile: u32, frames: Vec<TileFrame>) -> Self {
+        self.tiles.entry(tile).or_default().animation = frames;
+        self
+    }
+
+    pub fn info(&self, tile: u32) -> Option<&TileInfo> {
+        self.tiles.get(&tile)
+    }
+
+    pub fn is_solid(&self, tile: u32) -> bool {
+        self.info(tile).is_some_and(|info| info.solid)
+    }
+
+    pub fn is_animated(&self, tile: u32) -> bool {
+        self.info(tile)
+            .is_some_and(|info| !info.animation.is_empty())
+    }
+
+    /// The part of the image that displays the tile (in image pixels).
+    pub fn source(&self, tile: u32) -> Dimensions {
+        let column = (tile % self.columns) as f32;
+        let row = (tile / self.columns) as f32;
+        Dimensions::from_top_left(
+            Vertex2::new(
+                self.margin + column * (self.tile_size.x + self.spacing),
+                self.margin + row * (self.tile_size.y + self.spacing),
+            ),
+            self.tile_size.x,
+            self.tile_size.y,
+        )
+    }
+
+    /// The tile that's displayed in place of the tile at the given time (in seconds) - the tile
+    /// itself unless it's animated.
+    pub fn animated_tile(&self, tile: u32, time: f32) -> u32 {
+        let Some(info) = self.info(tile) else {
+            return tile;
+        };
+
+        let total: f32 = info.animation.iter().map(|frame| frame.duration).sum();
+        if total <= 0.0 {
+            return tile;
+        }
+
+        let mut time = time % total;
+        for frame in &info.animation {
+            if time < frame.duration {
+                return frame.tile;
+            }
+            time -= frame.duration;
+        }
+        info.animation.last().map_or(tile, |frame| frame.tile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tileset() -> Tileset {
+        Tileset::new(ImageHandle::new(0, 0), Vertex2::new(16.0, 8.0), 4, 8)
+    }
+
+    #[test]
+    fn source() {
+        let tileset = tileset().with_spacing(1.0, 2.0);
+
+        assert_eq!(
+            tileset.source(5),
+            Dimensions::from_top_left(Vertex2::new(19.0, 11.0), 16.0, 8.0)
+        );
+    }
+
+    #[test]
+    fn animated_tile() {
+        let frames = vec![
+            TileFrame {
+                tile: 1,
+                duration: 0.5,
+            },
+            TileFrame {
+                tile: 2,
+                duration: 1.0,
+            },
+        ];
+        let tileset = tileset().with_animation(0, frames);
+
+        assert!(tileset.is_animated(0));
+        assert_eq!(tileset.animated_tile(0, 0.25), 1);
+        assert_eq!(tileset.animated_tile(0, 1.0), 2);
+        assert_eq!(tileset.animated_tile(0, 1.75), 1);
+        assert_eq!(tileset.animated_tile(3, 1.0), 3);
+    }
+}
@@ -14,6 +14,29 @@ impl<T> Vertex2<T> {
     }
 }
 
+impl Vertex2<f32> {
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// The vertex scaled to a length of `1` - `None` for `(0, 0)` since it has no direction.
+    pub fn normalize(self) -> Option<Self> {
+        let length = self.length();
+        if length == 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            x: self.x / length,
+            y: self.y / length,
+        })
+    }
+
+    pub fn dot(self, rhs: Self) -> f32 {
+        self.x * rhs.x + self.y * rhs.y
+    }
+}
+
 macro_rules! bin_op_impl {
     ($trait:ident, $method:ident, $op:tt, $lhs:ty, $rhs:ty) => {
         impl<T> $trait<$rhs> for $lhs
@@ -95,6 +118,19 @@ mod tests {
         assert_eq!(vertex.y, 2.0);
     }
 
+    #[test]
+    fn length_and_normalize() {
+        let vertex = Vertex2::new(3.0, -4.0);
+        assert_eq!(vertex.length(), 5.0);
+        assert_eq!(vertex.normalize(), Some(Vertex2::new(0.6, -0.8)));
+        assert_eq!(Vertex2::new(0.0, 0.0).normalize(), None);
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(Vertex2::new(1.0, 2.0).dot(Vertex2::new(3.0, -4.0)), -5.0);
+    }
+
     #[test]
     fn add() {
         let vertex = Vertex2::new(1.5, 2.0) + Vertex2::new(2.0, 3.0);
@@ -1,6 +1,6 @@
 use crate::build_error;
 use proc_macro2::TokenStream;
-use syn::{FieldsNamed, Meta};
+use syn::{Attribute, FieldsNamed, Meta};
 
 pub const CHILD_ATTR: &str = "child";
 pub const CHILDREN_ATTR: &str = "children";
@@ -32,18 +32,24 @@ pub fn parse_fields(fields: &FieldsNamed) -> Result<Vec<Field>, TokenStream> {
     let mut child_cmp_fields = Vec::default();
 
     for field in &fields.named {
-        if field.attrs.is_empty() {
+        // Doc comments are attributes as well
+        let attrs: Vec<&Attribute> = field
+            .attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident("doc"))
+            .collect();
+        if attrs.is_empty() {
             continue;
         }
 
-        if field.attrs.len() > 1 {
+        if attrs.len() > 1 {
             return Err(build_error(field, "expected a single attribute per field"));
         }
 
         let unsupported_attr_err_msg =
             format!("expected \"#[{}]\" or \"#[{}]\"", CHILD_ATTR, CHILDREN_ATTR);
 
-        let attr = field.attrs.first().unwrap();
+        let attr = attrs[0];
         let path = match &attr.meta {
             Meta::Path(path) => path,
             _ => return Err(build_error(attr, &unsupported_attr_err_msg)),